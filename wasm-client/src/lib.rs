@@ -34,6 +34,16 @@ use crate::models::course::delete_course;
 
 #[wasm_bindgen(start)]
 pub async fn main() -> Result<(), JsValue> {
+    let courses: Vec<Course> = models::course::get_courses_by_teacher(1).await.unwrap();
+    for c in courses.iter() {
+        append_course_row(c)?;
+    }
+
+    Ok(())
+}
+
+/// 在课程表格末尾添加一行，服务端返回的课程可以直接渲染，不需要重新加载页面
+pub fn append_course_row(c: &Course) -> Result<(), JsValue> {
     let window = web_sys::window().expect("no global window exists");
     let document = window.document().expect("should have a document exists");
 
@@ -41,64 +51,66 @@ pub async fn main() -> Result<(), JsValue> {
         .get_element_by_id("left-tbody")
         .expect("left div not exists");
 
-    let courses: Vec<Course> = models::course::get_courses_by_teacher(1).await.unwrap();
-    for c in courses.iter() {
-        let tr = document.create_element("tr")?;
-        tr.set_attribute("id", format!("tr-{}", c.id).as_str())?;
-        // course id
-        let td = document.create_element("td")?;
-        td.set_text_content(Some(format!("{}", c.id).as_str()));
-        tr.append_child(&td)?;
-        // course name
-        let td = document.create_element("td")?;
-        td.set_text_content(Some(c.name.as_str()));
-        tr.append_child(&td)?;
-        // course time
-        let td = document.create_element("td")?;
-        td.set_text_content(Some(c.time.format("%Y-%m-%d").to_string().as_str()));
-        tr.append_child(&td)?;
-        // course description
-        let td = document.create_element("td")?;
-        if let Some(desc) = &c.description.clone() {
-            td.set_text_content(Some(desc.as_str()));
-        }
-        tr.append_child(&td)?;
-        // append button
-        let td = document.create_element("td")?;
-        let btn: HtmlButtonElement = document
-            .create_element("button")
-            .unwrap()
-            .dyn_into::<HtmlButtonElement>()
-            .unwrap();
-
-        let cid = c.id;
-        let click_closure = Closure::wrap(Box::new(
-            move |_event: web_sys::MouseEvent| {
-                let r = confirm(format!("Are you sure to delete course {}?", cid).as_str());
-                match r {
-                    true => {
-                        spawn_local(delete_course(1, cid));
-                        alert("deleted!");
-
-                        // reload
-                        web_sys::window().unwrap().location().reload().unwrap();
+    let tr = document.create_element("tr")?;
+    tr.set_attribute("id", format!("tr-{}", c.id).as_str())?;
+    // course id
+    let td = document.create_element("td")?;
+    td.set_text_content(Some(format!("{}", c.id).as_str()));
+    tr.append_child(&td)?;
+    // course name
+    let td = document.create_element("td")?;
+    td.set_text_content(Some(c.name.as_str()));
+    tr.append_child(&td)?;
+    // course time
+    let td = document.create_element("td")?;
+    td.set_text_content(Some(c.time.format("%Y-%m-%d").to_string().as_str()));
+    tr.append_child(&td)?;
+    // course description
+    let td = document.create_element("td")?;
+    if let Some(desc) = &c.description.clone() {
+        td.set_text_content(Some(desc.as_str()));
+    }
+    tr.append_child(&td)?;
+    // append button
+    let td = document.create_element("td")?;
+    let btn: HtmlButtonElement = document
+        .create_element("button")
+        .unwrap()
+        .dyn_into::<HtmlButtonElement>()
+        .unwrap();
+
+    let cid = c.id;
+    let click_closure = Closure::wrap(Box::new(
+        move |_event: web_sys::MouseEvent| {
+            let r = confirm(format!("Are you sure to delete course {}?", cid).as_str());
+            if r {
+                spawn_local(async move {
+                    match delete_course(1, cid).await {
+                        Ok(()) => {
+                            // 删除成功后直接移除对应的行
+                            let document = web_sys::window().unwrap().document().unwrap();
+                            if let Some(tr) = document.get_element_by_id(format!("tr-{}", cid).as_str()) {
+                                tr.remove();
+                            }
+                            alert("deleted!");
+                        }
+                        Err(err) => alert(format!("Failed to delete course: {:?}", err).as_str()),
                     }
-                    _ => {}
-                }
-            }) as Box<dyn Fn(_)>);
+                });
+            }
+        }) as Box<dyn Fn(_)>);
 
-        // convert to `Function` and pass to `addEventListener`
-        btn.add_event_listener_with_callback("click", click_closure.as_ref().unchecked_ref())?;
-        // prevent memory leak
-        click_closure.forget();
+    // convert to `Function` and pass to `addEventListener`
+    btn.add_event_listener_with_callback("click", click_closure.as_ref().unchecked_ref())?;
+    // prevent memory leak
+    click_closure.forget();
 
-        btn.set_attribute("class", "btn btn-danger btn-sm")?;
-        btn.set_text_content(Some("Delete"));
-        td.append_child(&btn)?;
-        tr.append_child(&td)?;
+    btn.set_attribute("class", "btn btn-danger btn-sm")?;
+    btn.set_text_content(Some("Delete"));
+    td.append_child(&btn)?;
+    tr.append_child(&td)?;
 
-        left_body.append_child(&tr)?;
-    }
+    left_body.append_child(&tr)?;
 
     Ok(())
 }
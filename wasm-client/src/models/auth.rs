@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, RequestMode, Response};
+
+/// localStorage 中保存 access token 的键
+const ACCESS_TOKEN_KEY: &str = "access_token";
+
+/// 登录响应中客户端需要的部分
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+}
+
+fn local_storage() -> Result<web_sys::Storage, JsValue> {
+    let window = web_sys::window().ok_or("no windows exists".to_string())?;
+    window
+        .local_storage()?
+        .ok_or_else(|| JsValue::from_str("no local storage exists"))
+}
+
+/// 登录成功后把 access token 保存到 localStorage
+#[wasm_bindgen]
+pub async fn login(username: String, password: String) -> Result<JsValue, JsValue> {
+    let opts = RequestInit::new();
+    opts.set_method("POST");
+    opts.set_mode(RequestMode::Cors);
+
+    let body = serde_json::json!({ "username": username, "password": password }).to_string();
+    opts.set_body(&JsValue::from_str(&body));
+
+    let url = "http://localhost:3000/auth/login";
+    let request = Request::new_with_str_and_init(url, &opts)?;
+    request.headers().set("Content-Type", "application/json")?;
+    request.headers().set("Accept", "application/json")?;
+
+    let window = web_sys::window().ok_or("no windows exists".to_string())?;
+    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+
+    assert!(resp_value.is_instance_of::<Response>());
+
+    let resp: Response = resp_value.dyn_into().unwrap();
+    if !resp.ok() {
+        return Err(JsValue::from_str("Invalid username or password"));
+    }
+    let text = JsFuture::from(resp.text()?).await?;
+    let tokens: TokenResponse = serde_json::from_str(&text.as_string().unwrap_or_default())
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    local_storage()?.set_item(ACCESS_TOKEN_KEY, &tokens.access_token)?;
+
+    Ok(JsValue::TRUE)
+}
+
+/// 已登录时为请求加上 `Authorization: Bearer <token>`
+pub fn authorize(request: &Request) -> Result<(), JsValue> {
+    if let Some(token) = local_storage()?.get_item(ACCESS_TOKEN_KEY)? {
+        request
+            .headers()
+            .set("Authorization", &format!("Bearer {}", token))?;
+    }
+    Ok(())
+}
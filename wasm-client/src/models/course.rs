@@ -14,21 +14,45 @@ pub struct Course {
 
     pub description: Option<String>,
     pub format: Option<String>,
-    pub structure: Option<String>,
-    pub duration: Option<String>,
+    /// 课程总时长（分钟），由服务端根据课时计算
+    pub duration: i64,
     pub price: Option<i32>,
     pub language: Option<String>,
     pub level: Option<String>,
 }
 
+/// 服务端分页返回的课程列表
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoursePage {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub next: Option<String>,
+    pub items: Vec<Course>,
+}
+
+/// 服务端地址，分页响应中的 next 是相对于它的路径
+const API_BASE: &str = "http://localhost:3000";
+
+/// 获取教师的全部课程：沿着 next 逐页请求，直到最后一页
 pub async fn get_courses_by_teacher(teacher_id: i32) -> Result<Vec<Course>, MyError> {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-    opts.mode(RequestMode::Cors);
+    let mut courses = vec![];
+    let mut next = Some(format!("/courses/{}?limit=100", teacher_id));
+    while let Some(path) = next {
+        let page = get_course_page(&format!("{}{}", API_BASE, path)).await?;
+        courses.extend(page.items);
+        next = page.next;
+    }
 
-    let url = &format!("http://localhost:3000/courses/{}", teacher_id);
+    Ok(courses)
+}
 
-    let request = Request::new_with_str_and_init(&url, &opts)?;
+async fn get_course_page(url: &str) -> Result<CoursePage, MyError> {
+    let opts = RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(RequestMode::Cors);
+
+    let request = Request::new_with_str_and_init(url, &opts)?;
     request.headers().set("Accept", "application/json")?;
 
     let window = web_sys::window().ok_or("no windows exists".to_string())?;
@@ -37,39 +61,42 @@ pub async fn get_courses_by_teacher(teacher_id: i32) -> Result<Vec<Course>, MyEr
     assert!(resp_value.is_instance_of::<Response>());
 
     let resp: Response = resp_value.dyn_into().unwrap();
+    if !resp.ok() {
+        return Err(MyError::SomeError(format!("Get courses failed with status {}", resp.status())));
+    }
     let json = JsFuture::from(resp.json()?).await?;
 
-    let courses: Vec<Course> = json.into_serde().unwrap();
-
-    Ok(courses)
+    serde_wasm_bindgen::from_value(json).map_err(|err| MyError::SomeError(err.to_string()))
 }
 
-pub async fn delete_course(teacher_id: i32, course_id: i32) -> () {
+pub async fn delete_course(teacher_id: i32, course_id: i32) -> Result<(), MyError> {
     let mut opts = RequestInit::new();
     opts.method("DELETE");
     opts.mode(RequestMode::Cors);
 
     let url = format!("http://localhost:3000/courses/{}/{}", teacher_id, course_id);
 
-    let request = Request::new_with_str_and_init(&url, &opts).unwrap();
-    request.headers().set("Accept", "application/json").unwrap();
+    let request = Request::new_with_str_and_init(&url, &opts)?;
+    request.headers().set("Accept", "application/json")?;
+    super::auth::authorize(&request)?;
 
-    let window = web_sys::window().unwrap();
-    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await.unwrap();
+    let window = web_sys::window().ok_or("no windows exists".to_string())?;
+    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
 
     assert!(resp_value.is_instance_of::<Response>());
 
+    // 删除成功时服务端返回 204，没有响应体
     let resp: Response = resp_value.dyn_into().unwrap();
-    let json = JsFuture::from(resp.json().unwrap()).await.unwrap();
-
-    let _courses: Course = json.into_serde().unwrap();
+    match resp.status() {
+        204 => Ok(()),
+        status => Err(MyError::SomeError(format!("Delete course failed with status {}", status))),
+    }
 }
 
-use js_sys::Promise;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-pub async fn add_course(name: String, description: String) -> Result<Promise, JsValue> {
+pub async fn add_course(name: String, description: String) -> Result<JsValue, JsValue> {
     let mut opts = RequestInit::new();
     opts.method("POST");
     opts.mode(RequestMode::Cors);
@@ -90,12 +117,18 @@ pub async fn add_course(name: String, description: String) -> Result<Promise, Js
     let request = Request::new_with_str_and_init(&url, &opts)?;
     request.headers().set("Content-Type", "application/json")?;
     request.headers().set("Accept", "application/json")?;
+    super::auth::authorize(&request)?;
 
     let window = web_sys::window().ok_or("no windows exists".to_string())?;
     let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
 
     assert!(resp_value.is_instance_of::<Response>());
 
+    // 服务端返回 201 和新建的课程，直接添加到表格中
     let resp: Response = resp_value.dyn_into().unwrap();
-    Ok(resp.json()?)
+    let json = JsFuture::from(resp.json()?).await?;
+    let course: Course = serde_wasm_bindgen::from_value(json.clone())?;
+    crate::append_course_row(&course)?;
+
+    Ok(json)
 }
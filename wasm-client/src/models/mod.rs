@@ -1 +1,2 @@
+pub mod auth;
 pub mod course;
\ No newline at end of file
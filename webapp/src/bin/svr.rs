@@ -1,6 +1,7 @@
 #[path = "../mod.rs"]
 mod webapp;
 
+use actix_web::middleware::from_fn;
 use actix_web::{web, App, HttpServer};
 use dotenv::dotenv;
 use routers::app_config;
@@ -22,6 +23,7 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(tera))
             .configure(app_config)
+            .wrap(from_fn(errors::request_id))
     };
 
     HttpServer::new(app).bind(&host_port)?.run().await
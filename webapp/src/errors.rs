@@ -1,18 +1,40 @@
-use actix_web::{error, http::StatusCode, HttpResponse, Result};
-use serde::Serialize;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{error, http::StatusCode, HttpMessage, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use uuid::Uuid;
+
+/// 请求 ID 所在的请求头和响应头，转发给 webservice 时沿用同一个值
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
 
 #[derive(Debug, Serialize)]
 pub enum MyError {
     ActixError(String),
+    /// 资源不存在，entity 为错误码的前缀，与 webservice 相同
     #[allow(dead_code)]
-    NotFound(String),
+    NotFound { entity: &'static str, msg: String },
     TeraError(String),
 }
 
-#[derive(Debug, Serialize)]
+/// 单个字段的错误，与 webservice 返回的 details 一致
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// 错误响应体，webapp 和 webservice 使用相同的格式
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MyErrorResponse {
-    error_message: String,
+    pub code: String,
+    pub error_message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl std::error::Error for MyError {}
@@ -28,25 +50,41 @@ impl MyError {
                 println!("Error in rendering the template: {:?}", msg);
                 msg.into()
             }
-            MyError::NotFound(msg) => {
+            MyError::NotFound { msg, .. } => {
                 println!("Not found error occurred: {:?}", msg);
                 msg.into()
             }
         }
     }
+
+    /// 错误码
+    pub fn code(&self) -> String {
+        match self {
+            MyError::ActixError(_msg) => "internal.error".into(),
+            MyError::TeraError(_msg) => "template.error".into(),
+            MyError::NotFound { entity, .. } => format!("{}.not_found", entity),
+        }
+    }
+
+    fn to_response(&self, request_id: Option<&str>) -> HttpResponse {
+        HttpResponse::build(error::ResponseError::status_code(self)).json(MyErrorResponse {
+            code: self.code(),
+            error_message: self.error_response(),
+            details: vec![],
+            request_id: request_id.map(String::from),
+        })
+    }
 }
 
 impl error::ResponseError for MyError {
     fn status_code(&self) -> StatusCode {
         match self {
             MyError::ActixError(_msg) | MyError::TeraError(_msg) => StatusCode::INTERNAL_SERVER_ERROR,
-            MyError::NotFound(_msg) => StatusCode::NOT_FOUND
+            MyError::NotFound { .. } => StatusCode::NOT_FOUND
         }
     }
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).json(MyErrorResponse {
-            error_message: self.error_response(),
-        })
+        self.to_response(None)
     }
 }
 
@@ -61,4 +99,41 @@ impl From<actix_web::error::Error> for MyError {
     fn from(err: actix_web::error::Error) -> Self {
         MyError::ActixError(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+/// 当前请求的 ID，处理器通过 `web::ReqData<RequestId>` 取得
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// 沿用客户端传入的 X-Request-Id（不超过 64 个字母、数字或 `-`），否则生成新的 UUID
+fn incoming_request_id(req: &ServiceRequest) -> Option<String> {
+    let id = req.headers().get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    valid.then(|| id.to_string())
+}
+
+/// 为每个请求分配请求 ID：写入响应头，并补到 MyError 生成的错误响应体中
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, error::Error> {
+    let id = incoming_request_id(&req).unwrap_or_else(|| Uuid::new_v4().to_string());
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let res = next.call(req).await?;
+    let rebuilt = res
+        .response()
+        .error()
+        .and_then(|err| err.as_error::<MyError>())
+        .map(|err| err.to_response(Some(&id)));
+    let mut res = match rebuilt {
+        Some(response) => res.into_response(response),
+        None => res.map_into_boxed_body(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(res)
+}
@@ -1,4 +1,4 @@
-use crate::errors::MyError;
+use crate::errors::{MyError, MyErrorResponse, RequestId, REQUEST_ID_HEADER};
 use crate::models::{TeacherRegisterForm, TeacherResponse};
 use actix_web::{web, Error, HttpResponse, Result};
 use serde_json::json;
@@ -33,6 +33,7 @@ pub async fn show_register_form(
 ) -> Result<HttpResponse, Error> {
     let mut ctx = tera::Context::new();
     ctx.insert("error", "");
+    ctx.insert("current_username", "");
     ctx.insert("current_name", "");
     ctx.insert("current_picture_url", "");
     ctx.insert("current_profile", "");
@@ -44,23 +45,36 @@ pub async fn show_register_form(
     Ok(HttpResponse::Ok().content_type("text/html").body(s))
 }
 
+/// 重新渲染注册表单，保留用户已填写的内容（不回填密码）
+fn render_register_error(
+    tmpl: &tera::Tera,
+    params: &TeacherRegisterForm,
+    error: &str,
+) -> Result<String, MyError> {
+    let mut ctx = tera::Context::new();
+    ctx.insert("error", error);
+    ctx.insert("current_username", &params.username);
+    ctx.insert("current_name", &params.name);
+    ctx.insert("current_picture_url", &params.picture_url);
+    ctx.insert("current_profile", &params.profile);
+    tmpl.render("register.html", &ctx)
+        .map_err(|err| MyError::TeraError(err.to_string()))
+}
+
 pub async fn handle_register(
     tmpl: web::Data<tera::Tera>,
     params: web::Form<TeacherRegisterForm>,
+    request_id: web::ReqData<RequestId>,
 ) -> Result<HttpResponse, Error> {
-    let mut ctx = tera::Context::new();
     let s;
 
     if params.name == "Dave" {
-        ctx.insert("error", "Dave already exists!");
-        ctx.insert("current_name", &params.name);
-        ctx.insert("current_picture_url", &params.picture_url);
-        ctx.insert("current_profile", &params.profile);
-        s = tmpl
-            .render("register.html", &ctx)
-            .map_err(|err| MyError::TeraError(err.to_string()))?;
+        s = render_register_error(&tmpl, &params, "Dave already exists!")?;
     } else {
+        // 通过 /auth/register 同时创建教师资料和登录账号
         let new_teacher = json!({
+            "username": &params.username,
+            "password": &params.password,
             "name": &params.name,
             "picture_url": &params.picture_url,
             "profile": &params.profile,
@@ -68,18 +82,37 @@ pub async fn handle_register(
 
         let awc_client = awc::Client::default();
 
-        let res = awc_client
-            .post("http://localhost:3000/teachers/")
+        let mut res = awc_client
+            .post("http://localhost:3000/auth/register")
+            .insert_header((REQUEST_ID_HEADER, request_id.0.as_str()))
             .send_json(&new_teacher)
             .await
-            .unwrap()
-            .body()
-            .await?;
+            .unwrap();
+        let body = res.body().await?;
 
-        let teacher_response: String =
-            serde_json::from_str(&std::str::from_utf8(&res)?)?;
-        s = format!("Message from Web Server: {}", teacher_response);
+        if res.status().is_success() {
+            let teacher_response: TeacherResponse =
+                serde_json::from_str(std::str::from_utf8(&body)?)?;
+            s = format!(
+                "Message from Web Server: Teacher {} registered with id {}",
+                teacher_response.name, teacher_response.id
+            );
+        } else {
+            // 用户名已存在、密码太短等错误直接显示在注册表单上，字段校验错误逐条列出
+            let error: MyErrorResponse = serde_json::from_slice(&body)?;
+            let message = if error.details.is_empty() {
+                error.error_message
+            } else {
+                error
+                    .details
+                    .iter()
+                    .map(|detail| format!("{}: {}", detail.field, detail.message))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            s = render_register_error(&tmpl, &params, &message)?;
+        }
     }
 
     Ok(HttpResponse::Ok().content_type("text/html").body(s))
-}
\ No newline at end of file
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 /// 教师信息，用于应用注册
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TeacherRegisterForm {
+    pub username: String,
+    pub password: String,
     pub name: String,
     pub picture_url: String,
     pub profile: String,
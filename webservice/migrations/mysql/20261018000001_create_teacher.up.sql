@@ -0,0 +1,10 @@
+-- 已有数据库中 teacher 表早于迁移存在，跳过创建并由后续迁移补齐
+CREATE TABLE IF NOT EXISTS teacher
+(
+    id          INT           NOT NULL AUTO_INCREMENT,
+    name        VARCHAR(100)  NOT NULL,
+    picture_url VARCHAR(200)  NOT NULL,
+    profile     VARCHAR(2000) NOT NULL,
+    PRIMARY KEY (id)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,18 @@
+-- 已有数据库中 course 表早于迁移存在，跳过创建并由后续迁移补齐
+CREATE TABLE IF NOT EXISTS course
+(
+    id          INT           NOT NULL AUTO_INCREMENT,
+    teacher_id  INT           NOT NULL,
+    name        VARCHAR(140)  NOT NULL,
+    time        DATETIME               DEFAULT CURRENT_TIMESTAMP,
+    description VARCHAR(2000),
+    format      VARCHAR(30),
+    structure   VARCHAR(200),
+    duration    VARCHAR(30),
+    price       INT,
+    language    VARCHAR(30),
+    level       VARCHAR(30),
+    PRIMARY KEY (id),
+    KEY idx_course_teacher_id (teacher_id)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,2 @@
+ALTER TABLE course
+    DROP FOREIGN KEY fk_course_teacher;
@@ -0,0 +1,3 @@
+ALTER TABLE course
+    ADD CONSTRAINT fk_course_teacher
+        FOREIGN KEY (teacher_id) REFERENCES teacher (id);
@@ -0,0 +1,2 @@
+ALTER TABLE course
+    DROP INDEX ft_course_search;
@@ -0,0 +1,3 @@
+-- ngram 分词器同时支持中文和英文的全文检索
+ALTER TABLE course
+    ADD FULLTEXT INDEX ft_course_search (name, description, structure) WITH PARSER ngram;
@@ -0,0 +1,12 @@
+CREATE TABLE account
+(
+    id            INT          NOT NULL AUTO_INCREMENT,
+    username      VARCHAR(100) NOT NULL,
+    password_hash VARCHAR(200) NOT NULL,
+    teacher_id    INT,
+    PRIMARY KEY (id),
+    UNIQUE KEY uk_account_username (username),
+    UNIQUE KEY uk_account_teacher_id (teacher_id),
+    CONSTRAINT fk_account_teacher FOREIGN KEY (teacher_id) REFERENCES teacher (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,32 @@
+-- 学生资料由对应的登录账号拥有，删除账号时一并删除
+CREATE TABLE student
+(
+    id         INT          NOT NULL AUTO_INCREMENT,
+    account_id INT          NOT NULL,
+    name       VARCHAR(100) NOT NULL,
+    email      VARCHAR(200) NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY uk_student_account_id (account_id),
+    CONSTRAINT fk_student_account FOREIGN KEY (account_id) REFERENCES account (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
+
+-- 课程容量，NULL 表示不限人数
+ALTER TABLE course
+    ADD COLUMN capacity INT;
+
+-- status 为 enrolled 或 waitlisted，候补名单按 enrolled_at、id 排序
+CREATE TABLE enrollment
+(
+    id          INT         NOT NULL AUTO_INCREMENT,
+    course_id   INT         NOT NULL,
+    student_id  INT         NOT NULL,
+    status      VARCHAR(20) NOT NULL,
+    enrolled_at DATETIME    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE KEY uk_enrollment_course_student (course_id, student_id),
+    KEY idx_enrollment_student_id (student_id),
+    CONSTRAINT fk_enrollment_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE,
+    CONSTRAINT fk_enrollment_student FOREIGN KEY (student_id) REFERENCES student (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
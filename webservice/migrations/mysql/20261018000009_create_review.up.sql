@@ -0,0 +1,18 @@
+-- 每个学生对每门课程只能评价一次，rating 取值 1-5
+CREATE TABLE review
+(
+    id         INT      NOT NULL AUTO_INCREMENT,
+    course_id  INT      NOT NULL,
+    student_id INT      NOT NULL,
+    rating     INT      NOT NULL,
+    comment    TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE KEY uk_review_course_student (course_id, student_id),
+    KEY idx_review_student_id (student_id),
+    CONSTRAINT ck_review_rating CHECK (rating BETWEEN 1 AND 5),
+    CONSTRAINT fk_review_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE,
+    CONSTRAINT fk_review_student FOREIGN KEY (student_id) REFERENCES student (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
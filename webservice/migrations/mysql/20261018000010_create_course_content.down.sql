@@ -0,0 +1,20 @@
+ALTER TABLE course
+    DROP INDEX ft_course_search;
+
+ALTER TABLE course
+    ADD COLUMN structure VARCHAR(200),
+    ADD COLUMN duration  VARCHAR(30);
+
+-- 第一个章节的标题还原为 structure，duration 无法还原
+UPDATE course
+SET structure = (SELECT LEFT(course_module.title, 200)
+                 FROM course_module
+                 WHERE course_module.course_id = course.id
+                 ORDER BY course_module.position, course_module.id
+                 LIMIT 1);
+
+ALTER TABLE course
+    ADD FULLTEXT INDEX ft_course_search (name, description, structure) WITH PARSER ngram;
+
+DROP TABLE IF EXISTS lesson;
+DROP TABLE IF EXISTS course_module;
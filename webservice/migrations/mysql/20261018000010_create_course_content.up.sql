@@ -0,0 +1,45 @@
+-- 课程内容：有序的章节，每个章节包含有序的课时；章节和课时的标题与课程一样建立全文索引，检索课程时一并匹配
+CREATE TABLE course_module
+(
+    id        INT          NOT NULL AUTO_INCREMENT,
+    course_id INT          NOT NULL,
+    title     VARCHAR(200) NOT NULL,
+    position  INT          NOT NULL,
+    PRIMARY KEY (id),
+    KEY idx_course_module_course_id (course_id, position),
+    FULLTEXT INDEX ft_course_module_search (title) WITH PARSER ngram,
+    CONSTRAINT fk_course_module_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
+
+CREATE TABLE lesson
+(
+    id                INT          NOT NULL AUTO_INCREMENT,
+    module_id         INT          NOT NULL,
+    title             VARCHAR(200) NOT NULL,
+    body              TEXT,
+    estimated_minutes INT          NOT NULL DEFAULT 0,
+    attachment_url    VARCHAR(500),
+    position          INT          NOT NULL,
+    PRIMARY KEY (id),
+    KEY idx_lesson_module_id (module_id, position),
+    FULLTEXT INDEX ft_lesson_search (title) WITH PARSER ngram,
+    CONSTRAINT fk_lesson_module FOREIGN KEY (module_id) REFERENCES course_module (id) ON DELETE CASCADE
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
+
+-- 原有的 structure 文本保留为课程的第一个章节，duration 改为由课时时长计算
+INSERT INTO course_module (course_id, title, position)
+SELECT id, structure, 1
+FROM course
+WHERE structure IS NOT NULL AND structure <> '';
+
+ALTER TABLE course
+    DROP INDEX ft_course_search;
+
+ALTER TABLE course
+    DROP COLUMN structure,
+    DROP COLUMN duration;
+
+ALTER TABLE course
+    ADD FULLTEXT INDEX ft_course_search (name, description) WITH PARSER ngram;
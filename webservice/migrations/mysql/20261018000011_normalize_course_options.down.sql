@@ -0,0 +1,8 @@
+-- 用备份表还原规范化之前的原始取值
+UPDATE course
+    JOIN course_option_backup ON course_option_backup.course_id = course.id
+SET course.format   = course_option_backup.format,
+    course.language = course_option_backup.language,
+    course.level    = course_option_backup.level;
+
+DROP TABLE course_option_backup;
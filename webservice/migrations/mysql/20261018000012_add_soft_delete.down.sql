@@ -0,0 +1,13 @@
+-- 去掉 deleted_at 之前把回收站中的数据物理删除，避免已删除的数据重新出现
+DELETE FROM course
+WHERE deleted_at IS NOT NULL;
+DELETE FROM teacher
+WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE course
+    DROP INDEX idx_course_deleted_at,
+    DROP COLUMN deleted_at;
+
+ALTER TABLE teacher
+    DROP INDEX idx_teacher_deleted_at,
+    DROP COLUMN deleted_at;
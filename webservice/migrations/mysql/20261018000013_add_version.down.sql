@@ -0,0 +1,5 @@
+ALTER TABLE course
+    DROP COLUMN version;
+
+ALTER TABLE teacher
+    DROP COLUMN version;
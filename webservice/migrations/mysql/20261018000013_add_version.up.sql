@@ -0,0 +1,6 @@
+-- 乐观并发控制：每次修改教师或课程都把 version 加一，写入时带上读取到的版本号
+ALTER TABLE teacher
+    ADD COLUMN version INT NOT NULL DEFAULT 1;
+
+ALTER TABLE course
+    ADD COLUMN version INT NOT NULL DEFAULT 1;
@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS audit_log;
+
+ALTER TABLE course
+    DROP COLUMN updated_at,
+    DROP COLUMN created_at;
+
+ALTER TABLE teacher
+    DROP COLUMN updated_at,
+    DROP COLUMN created_at;
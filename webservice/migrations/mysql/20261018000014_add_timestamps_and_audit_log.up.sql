@@ -0,0 +1,25 @@
+-- 教师和课程的创建、修改时间，已有数据取迁移时的时间
+ALTER TABLE teacher
+    ADD COLUMN created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+ALTER TABLE course
+    ADD COLUMN created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+-- 审计日志：每次增删改记录操作者、请求 ID 以及修改前后的值（JSON，修改时只含变化的字段）
+CREATE TABLE audit_log
+(
+    id         INT         NOT NULL AUTO_INCREMENT,
+    actor_id   INT,
+    action     VARCHAR(16) NOT NULL,
+    entity     VARCHAR(16) NOT NULL,
+    entity_id  INT         NOT NULL,
+    old_values TEXT,
+    new_values TEXT,
+    request_id VARCHAR(64),
+    created_at DATETIME    NOT NULL,
+    PRIMARY KEY (id),
+    KEY idx_audit_log_entity (entity, entity_id)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
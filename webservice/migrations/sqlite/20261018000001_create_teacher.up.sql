@@ -0,0 +1,8 @@
+-- 已有数据库中 teacher 表早于迁移存在，跳过创建并由后续迁移补齐
+CREATE TABLE IF NOT EXISTS teacher
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        VARCHAR(100)  NOT NULL,
+    picture_url VARCHAR(200)  NOT NULL,
+    profile     VARCHAR(2000) NOT NULL
+);
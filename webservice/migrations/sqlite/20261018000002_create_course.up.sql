@@ -0,0 +1,17 @@
+-- 已有数据库中 course 表早于迁移存在，跳过创建并由后续迁移补齐
+CREATE TABLE IF NOT EXISTS course
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    teacher_id  INTEGER      NOT NULL,
+    name        VARCHAR(140) NOT NULL,
+    time        DATETIME DEFAULT CURRENT_TIMESTAMP,
+    description VARCHAR(2000),
+    format      VARCHAR(30),
+    structure   VARCHAR(200),
+    duration    VARCHAR(30),
+    price       INTEGER,
+    language    VARCHAR(30),
+    level       VARCHAR(30)
+);
+
+CREATE INDEX IF NOT EXISTS idx_course_teacher_id ON course (teacher_id);
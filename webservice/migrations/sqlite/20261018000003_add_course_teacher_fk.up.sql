@@ -0,0 +1,25 @@
+-- SQLite 不支持 ALTER TABLE ADD CONSTRAINT，需要重建 course 表
+CREATE TABLE course_new
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    teacher_id  INTEGER      NOT NULL,
+    name        VARCHAR(140) NOT NULL,
+    time        DATETIME DEFAULT CURRENT_TIMESTAMP,
+    description VARCHAR(2000),
+    format      VARCHAR(30),
+    structure   VARCHAR(200),
+    duration    VARCHAR(30),
+    price       INTEGER,
+    language    VARCHAR(30),
+    level       VARCHAR(30),
+    CONSTRAINT fk_course_teacher FOREIGN KEY (teacher_id) REFERENCES teacher (id)
+);
+
+INSERT INTO course_new
+SELECT id, teacher_id, name, time, description, format, structure, duration, price, language, level
+FROM course;
+
+DROP TABLE course;
+ALTER TABLE course_new RENAME TO course;
+
+CREATE INDEX idx_course_teacher_id ON course (teacher_id);
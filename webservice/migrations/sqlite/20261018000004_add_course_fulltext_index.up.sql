@@ -0,0 +1,39 @@
+-- 以 course 表为外部内容的 FTS5 索引，由触发器保持同步
+CREATE VIRTUAL TABLE course_fts USING fts5
+(
+    name,
+    description,
+    structure,
+    content = 'course',
+    content_rowid = 'id'
+);
+
+INSERT INTO course_fts (rowid, name, description, structure)
+SELECT id, name, description, structure
+FROM course;
+
+CREATE TRIGGER course_fts_insert
+    AFTER INSERT
+    ON course
+BEGIN
+    INSERT INTO course_fts (rowid, name, description, structure)
+    VALUES (new.id, new.name, new.description, new.structure);
+END;
+
+CREATE TRIGGER course_fts_delete
+    AFTER DELETE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description, structure)
+    VALUES ('delete', old.id, old.name, old.description, old.structure);
+END;
+
+CREATE TRIGGER course_fts_update
+    AFTER UPDATE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description, structure)
+    VALUES ('delete', old.id, old.name, old.description, old.structure);
+    INSERT INTO course_fts (rowid, name, description, structure)
+    VALUES (new.id, new.name, new.description, new.structure);
+END;
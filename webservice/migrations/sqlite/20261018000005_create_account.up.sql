@@ -0,0 +1,10 @@
+CREATE TABLE account
+(
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    username      VARCHAR(100) NOT NULL,
+    password_hash VARCHAR(200) NOT NULL,
+    teacher_id    INTEGER,
+    CONSTRAINT uk_account_username UNIQUE (username),
+    CONSTRAINT uk_account_teacher_id UNIQUE (teacher_id),
+    CONSTRAINT fk_account_teacher FOREIGN KEY (teacher_id) REFERENCES teacher (id) ON DELETE CASCADE
+);
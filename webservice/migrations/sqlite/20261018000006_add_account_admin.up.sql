@@ -0,0 +1,2 @@
+ALTER TABLE account
+    ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+ALTER TABLE account
+    ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT 0;
+
+UPDATE account
+SET is_admin = 1
+WHERE id IN (SELECT account_id FROM account_role WHERE role = 'admin');
+
+DROP TABLE IF EXISTS account_role;
+DROP TABLE IF EXISTS role;
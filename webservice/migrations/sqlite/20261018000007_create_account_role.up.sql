@@ -0,0 +1,32 @@
+CREATE TABLE role
+(
+    name VARCHAR(20) PRIMARY KEY
+);
+
+INSERT INTO role (name)
+VALUES ('admin'),
+       ('teacher'),
+       ('student');
+
+CREATE TABLE account_role
+(
+    account_id INTEGER     NOT NULL,
+    role       VARCHAR(20) NOT NULL,
+    PRIMARY KEY (account_id, role),
+    CONSTRAINT fk_account_role_account FOREIGN KEY (account_id) REFERENCES account (id) ON DELETE CASCADE,
+    CONSTRAINT fk_account_role_role FOREIGN KEY (role) REFERENCES role (name)
+);
+
+-- 原有的 is_admin 标记和教师账号迁移为对应的角色
+INSERT INTO account_role (account_id, role)
+SELECT id, 'admin'
+FROM account
+WHERE is_admin;
+
+INSERT INTO account_role (account_id, role)
+SELECT id, 'teacher'
+FROM account
+WHERE teacher_id IS NOT NULL;
+
+ALTER TABLE account
+    DROP COLUMN is_admin;
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS enrollment;
+
+ALTER TABLE course
+    DROP COLUMN capacity;
+
+DROP TABLE IF EXISTS student;
@@ -0,0 +1,29 @@
+-- 学生资料由对应的登录账号拥有，删除账号时一并删除
+CREATE TABLE student
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER      NOT NULL,
+    name       VARCHAR(100) NOT NULL,
+    email      VARCHAR(200) NOT NULL,
+    CONSTRAINT uk_student_account_id UNIQUE (account_id),
+    CONSTRAINT fk_student_account FOREIGN KEY (account_id) REFERENCES account (id) ON DELETE CASCADE
+);
+
+-- 课程容量，NULL 表示不限人数
+ALTER TABLE course
+    ADD COLUMN capacity INTEGER;
+
+-- status 为 enrolled 或 waitlisted，候补名单按 enrolled_at、id 排序
+CREATE TABLE enrollment
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    course_id   INTEGER     NOT NULL,
+    student_id  INTEGER     NOT NULL,
+    status      VARCHAR(20) NOT NULL,
+    enrolled_at DATETIME    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT uk_enrollment_course_student UNIQUE (course_id, student_id),
+    CONSTRAINT fk_enrollment_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE,
+    CONSTRAINT fk_enrollment_student FOREIGN KEY (student_id) REFERENCES student (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_enrollment_student_id ON enrollment (student_id);
@@ -0,0 +1,17 @@
+-- 每个学生对每门课程只能评价一次，rating 取值 1-5
+CREATE TABLE review
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    course_id  INTEGER  NOT NULL,
+    student_id INTEGER  NOT NULL,
+    rating     INTEGER  NOT NULL,
+    comment    TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT uk_review_course_student UNIQUE (course_id, student_id),
+    CONSTRAINT ck_review_rating CHECK (rating BETWEEN 1 AND 5),
+    CONSTRAINT fk_review_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE,
+    CONSTRAINT fk_review_student FOREIGN KEY (student_id) REFERENCES student (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_review_student_id ON review (student_id);
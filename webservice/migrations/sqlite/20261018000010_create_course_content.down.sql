@@ -0,0 +1,67 @@
+DROP TRIGGER IF EXISTS lesson_fts_update;
+DROP TRIGGER IF EXISTS lesson_fts_delete;
+DROP TRIGGER IF EXISTS lesson_fts_insert;
+DROP TRIGGER IF EXISTS course_module_fts_update;
+DROP TRIGGER IF EXISTS course_module_fts_delete;
+DROP TRIGGER IF EXISTS course_module_fts_insert;
+DROP TABLE IF EXISTS course_content_fts;
+
+DROP TRIGGER IF EXISTS course_fts_update;
+DROP TRIGGER IF EXISTS course_fts_delete;
+DROP TRIGGER IF EXISTS course_fts_insert;
+DROP TABLE IF EXISTS course_fts;
+
+ALTER TABLE course
+    ADD COLUMN structure VARCHAR(200);
+ALTER TABLE course
+    ADD COLUMN duration VARCHAR(30);
+
+-- 第一个章节的标题还原为 structure，duration 无法还原
+UPDATE course
+SET structure = (SELECT substr(course_module.title, 1, 200)
+                 FROM course_module
+                 WHERE course_module.course_id = course.id
+                 ORDER BY course_module.position, course_module.id
+                 LIMIT 1);
+
+CREATE VIRTUAL TABLE course_fts USING fts5
+(
+    name,
+    description,
+    structure,
+    content = 'course',
+    content_rowid = 'id'
+);
+
+INSERT INTO course_fts (rowid, name, description, structure)
+SELECT id, name, description, structure
+FROM course;
+
+CREATE TRIGGER course_fts_insert
+    AFTER INSERT
+    ON course
+BEGIN
+    INSERT INTO course_fts (rowid, name, description, structure)
+    VALUES (new.id, new.name, new.description, new.structure);
+END;
+
+CREATE TRIGGER course_fts_delete
+    AFTER DELETE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description, structure)
+    VALUES ('delete', old.id, old.name, old.description, old.structure);
+END;
+
+CREATE TRIGGER course_fts_update
+    AFTER UPDATE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description, structure)
+    VALUES ('delete', old.id, old.name, old.description, old.structure);
+    INSERT INTO course_fts (rowid, name, description, structure)
+    VALUES (new.id, new.name, new.description, new.structure);
+END;
+
+DROP TABLE IF EXISTS lesson;
+DROP TABLE IF EXISTS course_module;
@@ -0,0 +1,138 @@
+-- 课程内容：有序的章节，每个章节包含有序的课时
+CREATE TABLE course_module
+(
+    id        INTEGER PRIMARY KEY AUTOINCREMENT,
+    course_id INTEGER      NOT NULL,
+    title     VARCHAR(200) NOT NULL,
+    position  INTEGER      NOT NULL,
+    CONSTRAINT fk_course_module_course FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_course_module_course_id ON course_module (course_id, position);
+
+CREATE TABLE lesson
+(
+    id                INTEGER PRIMARY KEY AUTOINCREMENT,
+    module_id         INTEGER      NOT NULL,
+    title             VARCHAR(200) NOT NULL,
+    body              TEXT,
+    estimated_minutes INTEGER      NOT NULL DEFAULT 0,
+    attachment_url    VARCHAR(500),
+    position          INTEGER      NOT NULL,
+    CONSTRAINT fk_lesson_module FOREIGN KEY (module_id) REFERENCES course_module (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_lesson_module_id ON lesson (module_id, position);
+
+-- 原有的 structure 文本保留为课程的第一个章节，duration 改为由课时时长计算
+INSERT INTO course_module (course_id, title, position)
+SELECT id, structure, 1
+FROM course
+WHERE structure IS NOT NULL AND structure <> '';
+
+-- 触发器引用了 structure 列，删除列之前先重建不含 structure 的全文索引
+DROP TRIGGER IF EXISTS course_fts_update;
+DROP TRIGGER IF EXISTS course_fts_delete;
+DROP TRIGGER IF EXISTS course_fts_insert;
+DROP TABLE IF EXISTS course_fts;
+
+ALTER TABLE course
+    DROP COLUMN structure;
+ALTER TABLE course
+    DROP COLUMN duration;
+
+CREATE VIRTUAL TABLE course_fts USING fts5
+(
+    name,
+    description,
+    content = 'course',
+    content_rowid = 'id'
+);
+
+INSERT INTO course_fts (rowid, name, description)
+SELECT id, name, description
+FROM course;
+
+CREATE TRIGGER course_fts_insert
+    AFTER INSERT
+    ON course
+BEGIN
+    INSERT INTO course_fts (rowid, name, description)
+    VALUES (new.id, new.name, new.description);
+END;
+
+CREATE TRIGGER course_fts_delete
+    AFTER DELETE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description)
+    VALUES ('delete', old.id, old.name, old.description);
+END;
+
+CREATE TRIGGER course_fts_update
+    AFTER UPDATE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description)
+    VALUES ('delete', old.id, old.name, old.description);
+    INSERT INTO course_fts (rowid, name, description)
+    VALUES (new.id, new.name, new.description);
+END;
+
+-- 章节和课时标题的全文索引，检索课程时一并匹配。
+-- 章节和课时的 id 会重复，rowid 取章节 id * 2 或课时 id * 2 + 1，course_id 用于关联到课程
+CREATE VIRTUAL TABLE course_content_fts USING fts5
+(
+    title,
+    course_id UNINDEXED
+);
+
+INSERT INTO course_content_fts (rowid, title, course_id)
+SELECT id * 2, title, course_id
+FROM course_module;
+
+CREATE TRIGGER course_module_fts_insert
+    AFTER INSERT
+    ON course_module
+BEGIN
+    INSERT INTO course_content_fts (rowid, title, course_id)
+    VALUES (new.id * 2, new.title, new.course_id);
+END;
+
+CREATE TRIGGER course_module_fts_delete
+    AFTER DELETE
+    ON course_module
+BEGIN
+    DELETE FROM course_content_fts WHERE rowid = old.id * 2;
+END;
+
+CREATE TRIGGER course_module_fts_update
+    AFTER UPDATE OF title
+    ON course_module
+BEGIN
+    UPDATE course_content_fts SET title = new.title WHERE rowid = new.id * 2;
+END;
+
+CREATE TRIGGER lesson_fts_insert
+    AFTER INSERT
+    ON lesson
+BEGIN
+    INSERT INTO course_content_fts (rowid, title, course_id)
+    SELECT new.id * 2 + 1, new.title, course_id
+    FROM course_module
+    WHERE id = new.module_id;
+END;
+
+CREATE TRIGGER lesson_fts_delete
+    AFTER DELETE
+    ON lesson
+BEGIN
+    DELETE FROM course_content_fts WHERE rowid = old.id * 2 + 1;
+END;
+
+CREATE TRIGGER lesson_fts_update
+    AFTER UPDATE OF title
+    ON lesson
+BEGIN
+    UPDATE course_content_fts SET title = new.title WHERE rowid = new.id * 2 + 1;
+END;
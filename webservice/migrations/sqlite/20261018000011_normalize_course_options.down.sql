@@ -0,0 +1,8 @@
+-- 用备份表还原规范化之前的原始取值
+UPDATE course
+SET format   = (SELECT format FROM course_option_backup WHERE course_id = course.id),
+    language = (SELECT language FROM course_option_backup WHERE course_id = course.id),
+    level    = (SELECT level FROM course_option_backup WHERE course_id = course.id)
+WHERE id IN (SELECT course_id FROM course_option_backup);
+
+DROP TABLE course_option_backup;
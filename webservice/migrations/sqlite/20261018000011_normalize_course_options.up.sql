@@ -0,0 +1,37 @@
+-- 规范化之前先备份含有非规范取值的课程，回滚时据此还原
+CREATE TABLE course_option_backup
+(
+    course_id   INTEGER      NOT NULL PRIMARY KEY,
+    format      VARCHAR(30),
+    language    VARCHAR(30),
+    level       VARCHAR(30)
+);
+
+INSERT INTO course_option_backup (course_id, format, language, level)
+SELECT id, format, language, level
+FROM course
+WHERE level NOT IN ('Beginner', 'Intermediate', 'Advanced')
+   OR format NOT IN ('Video', 'Live', 'Text', 'Blended')
+   OR language NOT IN ('English', 'Chinese', 'Japanese', 'Korean', 'French', 'German', 'Spanish');
+
+-- 把 level、format、language 的历史取值统一为规范值，无法识别的取值置为 NULL，原值保留在备份表中
+UPDATE course SET level = 'Beginner' WHERE LOWER(TRIM(level)) IN ('beginner', '初级', '入门');
+UPDATE course SET level = 'Intermediate' WHERE LOWER(TRIM(level)) IN ('intermediate', '中级');
+UPDATE course SET level = 'Advanced' WHERE LOWER(TRIM(level)) IN ('advanced', '高级');
+UPDATE course SET level = NULL WHERE level NOT IN ('Beginner', 'Intermediate', 'Advanced');
+
+UPDATE course SET format = 'Video' WHERE LOWER(TRIM(format)) IN ('video', '视频', '录播');
+UPDATE course SET format = 'Live' WHERE LOWER(TRIM(format)) IN ('live', '直播');
+UPDATE course SET format = 'Text' WHERE LOWER(TRIM(format)) IN ('text', '图文');
+UPDATE course SET format = 'Blended' WHERE LOWER(TRIM(format)) IN ('blended', '混合');
+UPDATE course SET format = NULL WHERE format NOT IN ('Video', 'Live', 'Text', 'Blended');
+
+UPDATE course SET language = 'English' WHERE LOWER(TRIM(language)) IN ('english', '英语', '英文');
+UPDATE course SET language = 'Chinese' WHERE LOWER(TRIM(language)) IN ('chinese', '中文', '汉语');
+UPDATE course SET language = 'Japanese' WHERE LOWER(TRIM(language)) IN ('japanese', '日语');
+UPDATE course SET language = 'Korean' WHERE LOWER(TRIM(language)) IN ('korean', '韩语');
+UPDATE course SET language = 'French' WHERE LOWER(TRIM(language)) IN ('french', '法语');
+UPDATE course SET language = 'German' WHERE LOWER(TRIM(language)) IN ('german', '德语');
+UPDATE course SET language = 'Spanish' WHERE LOWER(TRIM(language)) IN ('spanish', '西班牙语');
+UPDATE course SET language = NULL
+WHERE language NOT IN ('English', 'Chinese', 'Japanese', 'Korean', 'French', 'German', 'Spanish');
@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS idx_course_deleted_at;
+DROP INDEX IF EXISTS idx_teacher_deleted_at;
+
+-- 去掉 deleted_at 之前把回收站中的数据物理删除，避免已删除的数据重新出现
+DELETE FROM course
+WHERE deleted_at IS NOT NULL;
+DELETE FROM teacher
+WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE course
+    DROP COLUMN deleted_at;
+ALTER TABLE teacher
+    DROP COLUMN deleted_at;
@@ -0,0 +1,8 @@
+-- 软删除：deleted_at 不为 NULL 的教师和课程进入回收站，超过保留期后由清理任务物理删除
+ALTER TABLE teacher
+    ADD COLUMN deleted_at DATETIME;
+ALTER TABLE course
+    ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX idx_teacher_deleted_at ON teacher (deleted_at);
+CREATE INDEX idx_course_deleted_at ON course (deleted_at);
@@ -0,0 +1,4 @@
+ALTER TABLE course
+    DROP COLUMN version;
+ALTER TABLE teacher
+    DROP COLUMN version;
@@ -0,0 +1,24 @@
+DROP TABLE IF EXISTS audit_log;
+
+DROP TRIGGER IF EXISTS course_timestamps;
+DROP TRIGGER IF EXISTS teacher_timestamps;
+
+DROP TRIGGER IF EXISTS course_fts_update;
+CREATE TRIGGER course_fts_update
+    AFTER UPDATE
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description)
+    VALUES ('delete', old.id, old.name, old.description);
+    INSERT INTO course_fts (rowid, name, description)
+    VALUES (new.id, new.name, new.description);
+END;
+
+ALTER TABLE course
+    DROP COLUMN updated_at;
+ALTER TABLE course
+    DROP COLUMN created_at;
+ALTER TABLE teacher
+    DROP COLUMN updated_at;
+ALTER TABLE teacher
+    DROP COLUMN created_at;
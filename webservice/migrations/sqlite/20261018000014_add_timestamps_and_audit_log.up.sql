@@ -0,0 +1,68 @@
+-- 教师和课程的创建、修改时间。SQLite 的 ADD COLUMN 不支持 CURRENT_TIMESTAMP 默认值，
+-- 已有数据直接回填，新插入的数据由触发器补上，效果与 MySQL 的默认值相同
+ALTER TABLE teacher
+    ADD COLUMN created_at DATETIME;
+ALTER TABLE teacher
+    ADD COLUMN updated_at DATETIME;
+ALTER TABLE course
+    ADD COLUMN created_at DATETIME;
+ALTER TABLE course
+    ADD COLUMN updated_at DATETIME;
+
+UPDATE teacher
+SET created_at = CURRENT_TIMESTAMP,
+    updated_at = CURRENT_TIMESTAMP;
+UPDATE course
+SET created_at = CURRENT_TIMESTAMP,
+    updated_at = CURRENT_TIMESTAMP;
+
+-- 触发器按创建的相反顺序执行，course_timestamps 中的 UPDATE 会先于 course_fts_insert 触发全文索引的更新；
+-- 全文索引只在名称和描述变化时更新，补时间戳不会再动到索引
+DROP TRIGGER IF EXISTS course_fts_update;
+CREATE TRIGGER course_fts_update
+    AFTER UPDATE OF name, description
+    ON course
+BEGIN
+    INSERT INTO course_fts (course_fts, rowid, name, description)
+    VALUES ('delete', old.id, old.name, old.description);
+    INSERT INTO course_fts (rowid, name, description)
+    VALUES (new.id, new.name, new.description);
+END;
+
+CREATE TRIGGER teacher_timestamps
+    AFTER INSERT
+    ON teacher
+    WHEN NEW.created_at IS NULL
+BEGIN
+    UPDATE teacher
+    SET created_at = CURRENT_TIMESTAMP,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER course_timestamps
+    AFTER INSERT
+    ON course
+    WHEN NEW.created_at IS NULL
+BEGIN
+    UPDATE course
+    SET created_at = CURRENT_TIMESTAMP,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE id = NEW.id;
+END;
+
+-- 审计日志：每次增删改记录操作者、请求 ID 以及修改前后的值（JSON，修改时只含变化的字段）
+CREATE TABLE audit_log
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id   INTEGER,
+    action     VARCHAR(16) NOT NULL,
+    entity     VARCHAR(16) NOT NULL,
+    entity_id  INTEGER     NOT NULL,
+    old_values TEXT,
+    new_values TEXT,
+    request_id VARCHAR(64),
+    created_at DATETIME    NOT NULL
+);
+
+CREATE INDEX idx_audit_log_entity ON audit_log (entity, entity_id);
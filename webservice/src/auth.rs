@@ -0,0 +1,260 @@
+use crate::errors::MyError;
+use crate::models::auth::{Account, Claims, Role, TokenKind, TokenResponse};
+use crate::state::AppState;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{http::header, web, Error, FromRequest, HttpRequest};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::Serialize;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+/// access token 默认有效期：15 分钟
+pub const ACCESS_TOKEN_TTL: i64 = 15 * 60;
+/// refresh token 默认有效期：7 天
+pub const REFRESH_TOKEN_TTL: i64 = 7 * 24 * 60 * 60;
+
+/// 签发和校验 JWT 所需的配置
+pub struct AuthConfig {
+    secret: String,
+    pub access_token_ttl: i64,
+    pub refresh_token_ttl: i64,
+}
+
+impl AuthConfig {
+    pub fn new(secret: impl Into<String>) -> Self {
+        AuthConfig {
+            secret: secret.into(),
+            access_token_ttl: ACCESS_TOKEN_TTL,
+            refresh_token_ttl: REFRESH_TOKEN_TTL,
+        }
+    }
+
+    /// 为账号签发一对新的 access / refresh token，令牌中携带账号当前的角色
+    pub fn issue_tokens(&self, account: &Account, roles: &[Role]) -> Result<TokenResponse, MyError> {
+        Ok(TokenResponse {
+            access_token: self.encode_token(account, roles, TokenKind::Access, self.access_token_ttl)?,
+            refresh_token: self.encode_token(account, roles, TokenKind::Refresh, self.refresh_token_ttl)?,
+            token_type: "Bearer".into(),
+            expires_in: self.access_token_ttl,
+        })
+    }
+
+    /// 校验签名、过期时间和令牌类型，返回其中的声明
+    pub fn decode_token(&self, token: &str, kind: TokenKind) -> Result<Claims, MyError> {
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map(|data| data.claims)
+        .map_err(|_err| MyError::Unauthorized("Invalid or expired token".into()))?;
+
+        if claims.kind != kind {
+            return Err(MyError::Unauthorized("Invalid or expired token".into()));
+        }
+        Ok(claims)
+    }
+
+    fn encode_token(
+        &self,
+        account: &Account,
+        roles: &[Role],
+        kind: TokenKind,
+        ttl: i64,
+    ) -> Result<String, MyError> {
+        let now = Utc::now();
+        let claims = Claims {
+            account_id: account.id,
+            username: account.username.clone(),
+            teacher_id: account.teacher_id,
+            student_id: account.student_id,
+            roles: roles.to_vec(),
+            kind,
+            iat: now.timestamp(),
+            exp: (now + Duration::seconds(ttl)).timestamp(),
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|err| MyError::ActixError(err.to_string()))
+    }
+}
+
+/// 使用随机盐生成 argon2 密码哈希
+pub fn hash_password(password: &str) -> Result<String, MyError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|err| MyError::ActixError(err.to_string()))
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// 通过 `Authorization: Bearer <token>` 认证的调用者
+///
+/// 作为处理器参数使用时，缺少或无效的令牌会直接返回 401
+#[derive(Serialize, Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub account_id: i32,
+    pub username: String,
+    pub teacher_id: Option<i32>,
+    pub student_id: Option<i32>,
+    pub roles: Vec<Role>,
+}
+
+impl AuthenticatedUser {
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.has_role(Role::Admin)
+    }
+
+    /// 调用者至少拥有其中一个角色，否则返回 Forbidden
+    pub fn ensure_any_role(&self, roles: &[Role]) -> Result<(), MyError> {
+        if roles.iter().any(|role| self.has_role(*role)) {
+            Ok(())
+        } else {
+            Err(MyError::Forbidden("Insufficient role for this operation".into()))
+        }
+    }
+
+    /// 只有学生本人或管理员可以查看和修改该学生的选课，否则返回 Forbidden
+    pub fn ensure_student(&self, student_id: i32) -> Result<(), MyError> {
+        if self.is_admin() || self.student_id == Some(student_id) {
+            Ok(())
+        } else {
+            Err(MyError::Forbidden("Not allowed to access another student".into()))
+        }
+    }
+
+    /// 只有教师本人或管理员可以修改该教师及其课程，否则返回 Forbidden
+    pub fn ensure_owner(&self, teacher_id: i32) -> Result<(), MyError> {
+        if self.is_admin() || self.teacher_id == Some(teacher_id) {
+            Ok(())
+        } else {
+            Err(MyError::Forbidden(
+                "Not allowed to modify resources of another teacher".into(),
+            ))
+        }
+    }
+}
+
+impl From<Claims> for AuthenticatedUser {
+    fn from(claims: Claims) -> Self {
+        AuthenticatedUser {
+            account_id: claims.account_id,
+            username: claims.username,
+            teacher_id: claims.teacher_id,
+            student_id: claims.student_id,
+            roles: claims.roles,
+        }
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = MyError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(authenticate(req))
+    }
+}
+
+pub(crate) fn authenticate(req: &HttpRequest) -> Result<AuthenticatedUser, MyError> {
+    let app_state = req
+        .app_data::<web::Data<AppState>>()
+        .ok_or_else(|| MyError::ActixError("App state is not configured".into()))?;
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| MyError::Unauthorized("Missing bearer token".into()))?;
+
+    app_state
+        .auth
+        .decode_token(token.trim(), TokenKind::Access)
+        .map(AuthenticatedUser::from)
+}
+
+/// 声明式的路由守卫，用法：`web::post().to(handler).wrap(require_role(&[Role::Admin]))`
+///
+/// 未登录时返回 401，已登录但不具备任一角色时返回 403
+pub fn require_role(roles: &'static [Role]) -> RequireRole {
+    RequireRole { roles }
+}
+
+#[derive(Clone)]
+pub struct RequireRole {
+    roles: &'static [Role],
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireRoleMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware {
+            service,
+            roles: self.roles,
+        }))
+    }
+}
+
+pub struct RequireRoleMiddleware<S> {
+    service: S,
+    roles: &'static [Role],
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        match authenticate(req.request()).and_then(|user| user.ensure_any_role(self.roles)) {
+            Ok(()) => {
+                let fut = self.service.call(req);
+                Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+            }
+            Err(err) => {
+                let resp = req.error_response(err).map_into_right_body();
+                Box::pin(async move { Ok(resp) })
+            }
+        }
+    }
+}
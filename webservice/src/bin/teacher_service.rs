@@ -1,11 +1,13 @@
+use actix_web::middleware::from_fn;
 use actix_web::{http, web, App, HttpServer};
 use std::sync::Mutex;
 use dotenv::dotenv;
-use sqlx::mysql::MySqlPoolOptions;
 use std::env;
 use std::io;
 use actix_cors::Cors;
 
+#[path = "../auth.rs"]
+mod auth;
 #[path = "../dbaccess/mod.rs"]
 mod dbaccess;
 #[path = "../handlers/mod.rs"]
@@ -14,10 +16,21 @@ mod handlers;
 mod models;
 #[path = "../errors.rs"]
 mod errors;
+#[path = "../migration.rs"]
+mod migration;
+#[path = "../precondition.rs"]
+mod precondition;
+#[path = "../purge.rs"]
+mod purge;
 #[path = "../routers.rs"]
 mod routers;
 #[path = "../state.rs"]
 mod state;
+#[path = "../validation.rs"]
+mod validation;
+#[cfg(test)]
+#[path = "../test_utils.rs"]
+mod test_utils;
 
 use routers::*;
 use state::AppState;
@@ -31,44 +44,83 @@ async fn main() -> io::Result<()> {
     let db_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL 没有在 .env 文件里设置");
 
-    // 创建数据库连接池
-    let db_pool = MySqlPoolOptions::new()
-        .connect(&db_url)
+    // 根据 DATABASE_URL 创建 MySQL 或 SQLite 连接池
+    let db_pool = dbaccess::connect(&db_url)
         .await
         .unwrap();
 
+    // `teacher_service migrate ...` 只执行迁移命令，不启动服务
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("migrate") {
+        return migration::run_command(db_pool.as_ref(), &args[2..]).await;
+    }
+    // 启动服务和清理回收站之前都先应用尚未执行的迁移，保证 deleted_at 等列已经存在
+    db_pool
+        .run_pending_migrations()
+        .await
+        .expect("Failed to apply database migrations");
+
+    let retention = purge::retention_from_env();
+    // `teacher_service purge` 立即清理一次过期的回收站数据
+    if args.get(1).map(String::as_str) == Some("purge") {
+        let summary = purge::purge_expired(db_pool.as_ref(), retention)
+            .await
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        println!("Purged {} teacher(s) and {} course(s) from trash", summary.teachers, summary.courses);
+        return Ok(());
+    }
+
+    let jwt_secret = env::var("JWT_SECRET")
+        .expect("JWT_SECRET 没有在 .env 文件里设置");
+
     let shared_data = web::Data::new(AppState {
         health_check_response: "I'm OK.".to_string(),
         visit_count: Mutex::new(0),
         // courses: Mutex::new(vec![]),
         db: db_pool,
+        auth: auth::AuthConfig::new(jwt_secret),
     });
+    purge::spawn_purge_job(shared_data.clone(), retention);
 
     let app = move || {
-        let cors = Cors::default()
-            .allowed_origin("http://localhost:8082/")
-            .allowed_origin_fn(|origin, _req_head| {
-                origin.as_bytes().starts_with(b"http://localhost")
-            })
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"])
-            .allowed_headers(vec![
-                http::header::AUTHORIZATION,
-                http::header::ACCEPT,
-                http::header::CONTENT_TYPE,
-            ])
-            .allowed_header(http::header::CONTENT_TYPE)
-            .max_age(3600);
-
         App::new()
             .app_data(shared_data.clone())
-            .app_data(web::JsonConfig::default().error_handler(|_err, _req| {
-                MyError::InvalidInput("Please provide valid json input".to_string()).into()
+            .app_data(web::JsonConfig::default().error_handler(errors::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(|_err, _req| {
+                MyError::InvalidInput("Please provide valid query parameters".to_string()).into()
             }))
             .configure(general_routes)
+            .configure(auth_routes)
+            .configure(admin_routes)
             .configure(course_routes)
-            .wrap(cors)
+            .wrap(cors())
+            .wrap(from_fn(errors::request_id))
             .configure(teacher_routes)
+            .configure(student_routes)
+            .configure(trash_routes)
+            .configure(audit_routes)
     };
 
     HttpServer::new(app).bind("127.0.0.1:3000")?.run().await
-}
\ No newline at end of file
+}
+
+/// 允许本机前端跨域访问，错误响应同样带有 CORS 响应头
+fn cors() -> Cors {
+    Cors::default()
+        .allowed_origin("http://localhost:8082/")
+        .allowed_origin_fn(|origin, _req_head| {
+            origin.as_bytes().starts_with(b"http://localhost")
+        })
+        .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"])
+        .allowed_headers(vec![
+            http::header::AUTHORIZATION,
+            http::header::ACCEPT,
+            http::header::CONTENT_TYPE,
+            http::header::IF_MATCH,
+            http::header::IF_NONE_MATCH,
+            errors::REQUEST_ID_HEADER,
+        ])
+        .expose_headers(vec![http::header::ETAG, errors::REQUEST_ID_HEADER])
+        .allowed_header(http::header::CONTENT_TYPE)
+        .max_age(3600)
+}
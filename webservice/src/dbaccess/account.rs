@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use crate::errors::MyError;
+use crate::models::auth::{Account, Role};
+use crate::models::teacher::{CreateTeacher, Teacher};
+use crate::models::audit::AuditContext;
+
+#[async_trait]
+pub trait AccountRepository {
+    /// 在同一个事务中创建教师和与之绑定的登录账号，账号获得 teacher 角色
+    async fn register_teacher_db(
+        &self,
+        ctx: &AuditContext,
+        new_teacher: CreateTeacher,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<Teacher, MyError>;
+
+    /// 没有匹配的账号时返回 NotFound
+    async fn get_account_by_username_db(&self, username: &str) -> Result<Account, MyError>;
+
+    /// 没有匹配的账号时返回 NotFound
+    async fn get_account_db(&self, account_id: i32) -> Result<Account, MyError>;
+
+    /// 账号拥有的全部角色，按名称排序
+    async fn get_account_roles_db(&self, account_id: i32) -> Result<Vec<Role>, MyError>;
+
+    /// 授予角色，已拥有该角色时不做任何修改；账号不存在时返回 NotFound
+    async fn grant_role_db(&self, ctx: &AuditContext, account_id: i32, role: Role) -> Result<Vec<Role>, MyError>;
+
+    /// 撤销角色，未拥有该角色时不做任何修改；账号不存在时返回 NotFound
+    async fn revoke_role_db(&self, ctx: &AuditContext, account_id: i32, role: Role) -> Result<Vec<Role>, MyError>;
+}
+
+/// 把 account_role 表中的角色名称转换为 Role
+pub(crate) fn parse_roles(rows: Vec<(String,)>) -> Result<Vec<Role>, MyError> {
+    rows.into_iter()
+        .map(|(role,)| {
+            role.parse()
+                .map_err(|_err| MyError::DBError(format!("Unknown role in database: {}", role)))
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use std::convert::TryFrom;
+use crate::errors::MyError;
+use crate::models::audit::{AuditEntity, AuditEntry, AuditQuery};
+use sqlx::{Database, Encode, QueryBuilder, Type};
+
+/// 审计日志：数据访问层的每次增删改在同一事务中写入一条审计记录，与修改一起提交或回滚
+#[async_trait]
+pub trait AuditRepository {
+    /// 返回当前页的审计记录（按时间倒序）和满足过滤条件的记录总数
+    async fn get_audit_log_db(&self, query: &AuditQuery) -> Result<(Vec<AuditEntry>, i64), MyError>;
+}
+
+/// audit_log 表中的一行，修改前后的值以 JSON 文本保存
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuditRow {
+    id: i32,
+    actor_id: Option<i32>,
+    action: String,
+    entity: String,
+    entity_id: i32,
+    old_values: Option<String>,
+    new_values: Option<String>,
+    request_id: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl TryFrom<AuditRow> for AuditEntry {
+    type Error = MyError;
+
+    fn try_from(row: AuditRow) -> Result<Self, Self::Error> {
+        let parse_json = |text: Option<String>| {
+            text.map(|text| serde_json::from_str(&text))
+                .transpose()
+                .map_err(|err| MyError::DBError(format!("Invalid JSON in audit_log: {}", err)))
+        };
+        Ok(AuditEntry {
+            id: row.id,
+            actor_id: row.actor_id,
+            action: row.action.parse().map_err(|_err| {
+                MyError::DBError(format!("Unknown audit action in database: {}", row.action))
+            })?,
+            entity: row.entity.parse().map_err(|_err| {
+                MyError::DBError(format!("Unknown audit entity in database: {}", row.entity))
+            })?,
+            entity_id: row.entity_id,
+            before: parse_json(row.old_values)?,
+            after: parse_json(row.new_values)?,
+            request_id: row.request_id,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// 把审计日志的过滤条件以 `AND ...` 的形式追加到查询中，MySQL 和 SQLite 共用
+pub(crate) fn push_audit_filters<'args, DB>(
+    builder: &mut QueryBuilder<'args, DB>,
+    entity: Option<AuditEntity>,
+    entity_id: Option<i32>,
+) where
+    DB: Database,
+    String: Encode<'args, DB> + Type<DB>,
+    i32: Encode<'args, DB> + Type<DB>,
+{
+    if let Some(entity) = entity {
+        builder.push(" AND entity = ").push_bind(entity.to_string());
+    }
+    if let Some(entity_id) = entity_id {
+        builder.push(" AND entity_id = ").push_bind(entity_id);
+    }
+}
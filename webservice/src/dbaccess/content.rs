@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use crate::models::content::{
+    CourseModule, CreateLesson, CreateModule, Lesson, ModuleOutline, UpdateLesson, UpdateModule,
+};
+
+/// 课程内容：章节和课时的增删改查以及排序
+#[async_trait]
+pub trait ContentRepository {
+    /// 按顺序返回课程的全部章节及其课时，课程不存在时返回 NotFound
+    async fn get_course_modules_db(&self, teacher_id: i32, course_id: i32) -> Result<Vec<ModuleOutline>, MyError>;
+
+    async fn get_module_db(&self, teacher_id: i32, course_id: i32, module_id: i32) -> Result<ModuleOutline, MyError>;
+
+    /// 新章节追加到课程的最后
+    async fn post_new_module_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        new_module: CreateModule,
+    ) -> Result<CourseModule, MyError>;
+
+    async fn update_module_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+        update_module: UpdateModule,
+    ) -> Result<CourseModule, MyError>;
+
+    /// 删除章节时一并删除其中的课时，没有匹配的章节时返回 NotFound
+    async fn delete_module_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+    ) -> Result<(), MyError>;
+
+    /// module_ids 必须恰好列出课程的全部章节，否则返回 InvalidInput
+    async fn reorder_modules_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_ids: &[i32],
+    ) -> Result<Vec<ModuleOutline>, MyError>;
+
+    /// 新课时追加到章节的最后
+    async fn post_new_lesson_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+        new_lesson: CreateLesson,
+    ) -> Result<Lesson, MyError>;
+
+    async fn update_lesson_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+        lesson_id: i32,
+        update_lesson: UpdateLesson,
+    ) -> Result<Lesson, MyError>;
+
+    /// 没有匹配的课时时返回 NotFound
+    async fn delete_lesson_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+        lesson_id: i32,
+    ) -> Result<(), MyError>;
+
+    /// lesson_ids 必须恰好列出章节的全部课时，否则返回 InvalidInput
+    async fn reorder_lessons_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        module_id: i32,
+        lesson_ids: &[i32],
+    ) -> Result<ModuleOutline, MyError>;
+}
+
+/// 检查新的顺序是否恰好包含现有的全部 id，每个 id 只出现一次
+pub(crate) fn check_order(existing: &[i32], requested: &[i32]) -> Result<(), MyError> {
+    let existing: HashSet<i32> = existing.iter().copied().collect();
+    let requested_set: HashSet<i32> = requested.iter().copied().collect();
+    if requested_set.len() != requested.len() || requested_set != existing {
+        return Err(MyError::InvalidInput(
+            "ids must list every item exactly once".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// 调整顺序后位置发生变化的章节或课时，每个一条审计记录；existing 为调整前的 (id, position)
+pub(crate) fn position_changes(entity: AuditEntity, existing: &[(i32, i32)], requested: &[i32]) -> Vec<AuditRecord> {
+    let old_positions: HashMap<i32, i32> = existing.iter().copied().collect();
+    requested
+        .iter()
+        .enumerate()
+        .filter_map(|(index, id)| {
+            let old_position = old_positions.get(id).copied()?;
+            let new_position = index as i32 + 1;
+            (old_position != new_position).then(|| {
+                AuditRecord::updated(
+                    entity,
+                    *id,
+                    &json!({ "position": old_position }),
+                    &json!({ "position": new_position }),
+                )
+            })
+        })
+        .collect()
+}
+
+/// 把按顺序查出的章节和课时组装成大纲，课时已按 position 排好序
+pub(crate) fn module_outlines(modules: Vec<CourseModule>, lessons: Vec<Lesson>) -> Vec<ModuleOutline> {
+    let mut lessons_by_module: HashMap<i32, Vec<Lesson>> = HashMap::new();
+    for lesson in lessons {
+        lessons_by_module.entry(lesson.module_id).or_default().push(lesson);
+    }
+
+    modules
+        .into_iter()
+        .map(|module| {
+            let lessons = lessons_by_module.remove(&module.id).unwrap_or_default();
+            ModuleOutline::new(module, lessons)
+        })
+        .collect()
+}
+
+/// 把请求中提供的字段合并到当前的课时上
+pub(crate) fn apply_lesson_changes(current: Lesson, update_lesson: UpdateLesson) -> Lesson {
+    Lesson {
+        title: update_lesson.title.unwrap_or(current.title),
+        body: update_lesson.body.unwrap_or(current.body),
+        estimated_minutes: update_lesson.estimated_minutes.unwrap_or(current.estimated_minutes),
+        attachment_url: update_lesson.attachment_url.unwrap_or(current.attachment_url),
+        ..current
+    }
+}
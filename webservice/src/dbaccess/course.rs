@@ -1,167 +1,116 @@
+use async_trait::async_trait;
 use chrono::NaiveDateTime;
-use crate::models::course::{Course, CreateCourse, UpdateCourse};
+use crate::models::course::{Course, CourseQuery, CreateCourse, UpdateCourse};
 use crate::errors::MyError;
-use sqlx::MySqlPool;
+use crate::models::audit::AuditContext;
+use sqlx::{Database, Encode, QueryBuilder, Type};
 
-pub async fn post_new_course_db(pool: &MySqlPool, new_course: CreateCourse) -> Result<(), MyError> {
-    let _insert_query = sqlx::query!(
-        "INSERT INTO course (teacher_id, name, time, description, format, structure, duration, price, language, level)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        new_course.teacher_id,
-        new_course.name,
-        new_course.time,
-        new_course.description,
-        new_course.format,
-        new_course.structure,
-        new_course.duration,
-        new_course.price,
-        new_course.language,
-        new_course.level
-    )
-        .execute(pool)
-        .await?;
+#[async_trait]
+pub trait CourseRepository {
+    /// 新建课程并返回数据库中保存的完整记录
+    async fn post_new_course_db(&self, ctx: &AuditContext, new_course: CreateCourse) -> Result<Course, MyError>;
 
-    Ok(())
-}
-
-pub async fn delete_course_db(pool: &MySqlPool, teacher_id: i32, course_id: i32) -> Result<String, MyError> {
-    let row = sqlx::query!(
-        "DELETE FROM course
-            WHERE teacher_id = ? AND id = ?",
-        teacher_id,
-        course_id
-    )
-        .execute(pool)
-        .await?;
-
-    Ok(format!("Deleted {:?} record", row))
-}
+    /// 把课程移入回收站（设置 deleted_at），没有匹配的课程时返回 NotFound；
+    /// expected_version 不为 None 时只有版本号一致才删除，否则返回 PreconditionFailed
+    async fn delete_course_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        expected_version: Option<i32>,
+    ) -> Result<(), MyError>;
 
-pub async fn update_course_details_db(
-    pool: &MySqlPool,
-    teacher_id: i32,
-    course_id: i32,
-    update_course: UpdateCourse,
-) -> Result<String, MyError> {
-    let current_course_row: Course = sqlx::query_as(
-        "SELECT * FROM course
-            WHERE teacher_id = ? and id = ?"
-    )
-        .bind(teacher_id)
-        .bind(course_id)
-        .fetch_one(pool) // 获取单条记录
-        .await
-        .map_err(|_err| MyError::NotFound("Course Id not found".into()))?;
+    /// 只更新提供的字段并把版本号加一；expected_version 的含义同 delete_course_db
+    async fn update_course_details_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        update_course: UpdateCourse,
+        expected_version: Option<i32>,
+    ) -> Result<Course, MyError>;
 
-    let name: String = if let Some(name) = update_course.name {
-        name
-    } else {
-        current_course_row.name
-    };
-    let time: NaiveDateTime = if let Some(time) = update_course.time {
-        time
-    } else {
-        current_course_row
-            .time
-            .unwrap_or_default()
-    };
-    let description: String = if let Some(description) = update_course.description {
-        description
-    } else {
-        current_course_row
-            .description
-            .unwrap_or_default()
-    };
-    let format: String = if let Some(format) = update_course.format {
-        format
-    } else {
-        current_course_row
-            .format
-            .unwrap_or_default()
-    };
-    let structure: String = if let Some(structure) = update_course.structure {
-        structure
-    } else {
-        current_course_row
-            .structure
-            .unwrap_or_default()
-    };
-    let duration: String = if let Some(duration) = update_course.duration {
-        duration
-    } else {
-        current_course_row
-            .duration
-            .unwrap_or_default()
-    };
-    let level: String = if let Some(level) = update_course.level {
-        level
-    } else {
-        current_course_row
-            .level
-            .unwrap_or_default()
-    };
-    let language: String = if let Some(language) = update_course.language {
-        language
-    } else {
-        current_course_row
-            .language
-            .unwrap_or_default()
-    };
-    let price: i32 = if let Some(price) = update_course.price {
-        price
-    } else {
-        current_course_row
-            .price
-            .unwrap_or_default()
-    };
+    /// 返回当前页的课程和满足过滤条件的课程总数
+    async fn get_courses_for_teacher_db(
+        &self,
+        teacher_id: i32,
+        query: &CourseQuery,
+    ) -> Result<(Vec<Course>, i64), MyError>;
 
-    let row = sqlx::query!(
-        "UPDATE course
-            SET name = ?, time = ?, description = ?, format = ?, structure = ?, duration = ?, price = ?, language = ?, level = ?
-            WHERE teacher_id = ? AND id = ?",
-        name,
-        time,
-        description,
-        format,
-        structure,
-        duration,
-        price,
-        language,
-        level,
-        teacher_id,
-        course_id
-    )
-        .execute(pool)
-        .await?;
+    /// 跨教师检索整个课程目录，有关键字时默认按相关度排序
+    async fn search_courses_db(&self, query: &CourseQuery) -> Result<(Vec<Course>, i64), MyError>;
 
-    Ok(format!("Update {:?} record", row))
+    async fn get_course_details_db(&self, teacher_id: i32, course_id: i32) -> Result<Course, MyError>;
 }
 
-pub async fn get_courses_for_teacher_db(pool: &MySqlPool, teacher_id: i32) -> Result<Vec<Course>, MyError> {
-    let rows: Vec<Course> = sqlx::query_as(
-        "SELECT * FROM course
-                WHERE teacher_id = ?"
-    )
-        .bind(teacher_id)
-        .fetch_all(pool) // 获取所有记录
-        .await?;
-
-    Ok(rows)
+/// 把课程列表的过滤条件以 `AND ...` 的形式追加到查询中，MySQL 和 SQLite 共用
+pub(crate) fn push_course_filters<'args, DB>(builder: &mut QueryBuilder<'args, DB>, query: &CourseQuery)
+where
+    DB: Database,
+    String: Encode<'args, DB> + Type<DB>,
+    i32: Encode<'args, DB> + Type<DB>,
+    NaiveDateTime: Encode<'args, DB> + Type<DB>,
+{
+    // 取值已经在 CourseQuery::validate 中校验过，这里按规范值过滤
+    if let Ok(Some(level)) = query.level() {
+        builder.push(" AND level = ").push_bind(level.to_string());
+    }
+    if let Ok(Some(language)) = query.language() {
+        builder.push(" AND language = ").push_bind(language.to_string());
+    }
+    if let Ok(Some(format)) = query.format() {
+        builder.push(" AND format = ").push_bind(format.to_string());
+    }
+    if let Some(min_price) = query.min_price {
+        builder.push(" AND price >= ").push_bind(min_price);
+    }
+    if let Some(max_price) = query.max_price {
+        builder.push(" AND price <= ").push_bind(max_price);
+    }
+    if let Some(time_from) = query.time_from {
+        builder.push(" AND time >= ").push_bind(time_from);
+    }
+    if let Some(time_to) = query.time_to {
+        builder.push(" AND time <= ").push_bind(time_to);
+    }
 }
 
-pub async fn get_course_details_db(pool: &MySqlPool, teacher_id: i32, course_id: i32) -> Result<Course, MyError> {
-    let row = sqlx::query_as(
-        "SELECT * FROM course
-            WHERE teacher_id = ? and id = ?"
-    )
-        .bind(teacher_id)
-        .bind(course_id)
-        .fetch_optional(pool) // 获取单条记录
-        .await?;
-
-    if let Some(course) = row {
-        Ok(course)
-    } else {
-        Err(MyError::NotFound("Course didn't founded".into()))
+/// 把 PATCH 中提供的字段追加为 `SET` 子句，没有任何字段时返回 false
+pub(crate) fn push_course_changes<'args, DB>(builder: &mut QueryBuilder<'args, DB>, update_course: &UpdateCourse) -> bool
+where
+    DB: Database,
+    String: Encode<'args, DB> + Type<DB>,
+    i32: Encode<'args, DB> + Type<DB>,
+    NaiveDateTime: Encode<'args, DB> + Type<DB>,
+    Option<String>: Encode<'args, DB>,
+    Option<i32>: Encode<'args, DB>,
+    Option<NaiveDateTime>: Encode<'args, DB>,
+{
+    let mut changes = builder.separated(", ");
+    if let Some(name) = &update_course.name {
+        changes.push("name = ").push_bind_unseparated(name.clone());
+    }
+    if let Some(time) = update_course.time {
+        changes.push("time = ").push_bind_unseparated(time);
+    }
+    if let Some(description) = &update_course.description {
+        changes.push("description = ").push_bind_unseparated(description.clone());
+    }
+    if let Some(format) = &update_course.format {
+        changes.push("format = ").push_bind_unseparated(format.clone());
+    }
+    if let Some(price) = update_course.price {
+        changes.push("price = ").push_bind_unseparated(price);
     }
-}
\ No newline at end of file
+    if let Some(language) = &update_course.language {
+        changes.push("language = ").push_bind_unseparated(language.clone());
+    }
+    if let Some(level) = &update_course.level {
+        changes.push("level = ").push_bind_unseparated(level.clone());
+    }
+    if let Some(capacity) = update_course.capacity {
+        changes.push("capacity = ").push_bind_unseparated(capacity);
+    }
+
+    !update_course.is_empty()
+}
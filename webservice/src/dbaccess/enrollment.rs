@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use crate::errors::MyError;
+use crate::models::enrollment::{CourseRoster, Enrollment, EnrollmentStatus, StudentCourse};
+use crate::models::audit::AuditContext;
+
+#[async_trait]
+pub trait EnrollmentRepository {
+    /// 课程未满时直接选课，已满时进入候补名单；重复选课返回 Conflict
+    async fn enroll_student_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        student_id: i32,
+    ) -> Result<Enrollment, MyError>;
+
+    /// 退课后空出的名额按顺序递补给候补名单中的学生；没有选课记录时返回 NotFound
+    async fn withdraw_student_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        student_id: i32,
+    ) -> Result<(), MyError>;
+
+    async fn get_course_roster_db(&self, teacher_id: i32, course_id: i32) -> Result<CourseRoster, MyError>;
+
+    /// 学生选修和候补的全部课程，学生不存在时返回 NotFound
+    async fn get_student_courses_db(&self, student_id: i32) -> Result<Vec<StudentCourse>, MyError>;
+}
+
+/// 根据课程容量和已选人数决定新选课记录的状态
+pub(crate) fn enrollment_status(capacity: Option<i32>, enrolled: i64) -> EnrollmentStatus {
+    match capacity {
+        Some(capacity) if enrolled >= i64::from(capacity) => EnrollmentStatus::Waitlisted,
+        _ => EnrollmentStatus::Enrolled,
+    }
+}
+
+/// 容量允许递补的候补人数，None 表示全部递补
+pub(crate) fn open_seats(capacity: Option<i32>, enrolled: i64) -> Option<i64> {
+    capacity.map(|capacity| (i64::from(capacity) - enrolled).max(0))
+}
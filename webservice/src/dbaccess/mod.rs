@@ -1,2 +1,101 @@
+pub mod account;
+pub mod audit;
+pub mod content;
 pub mod course;
-pub mod teacher;
\ No newline at end of file
+pub mod enrollment;
+pub mod review;
+pub mod student;
+pub mod teacher;
+pub mod trash;
+pub mod unit_of_work;
+mod mysql;
+mod sql;
+mod sqlite;
+
+use crate::errors::MyError;
+use crate::migration::SchemaMigrator;
+use chrono::{NaiveDateTime, SubsecRound, Utc};
+use account::AccountRepository;
+use audit::AuditRepository;
+use content::ContentRepository;
+use course::CourseRepository;
+use enrollment::EnrollmentRepository;
+use review::ReviewRepository;
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::str::FromStr;
+use student::StudentRepository;
+use teacher::TeacherRepository;
+use trash::TrashRepository;
+use unit_of_work::UnitOfWorkRepository;
+
+/// 数据访问层对外暴露的全部能力，MySQL 和 SQLite 连接池都实现了它
+pub trait Repository:
+    AccountRepository
+    + AuditRepository
+    + ContentRepository
+    + CourseRepository
+    + EnrollmentRepository
+    + ReviewRepository
+    + StudentRepository
+    + TeacherRepository
+    + TrashRepository
+    + UnitOfWorkRepository
+    + SchemaMigrator
+    + Send
+    + Sync
+{
+}
+
+impl<T> Repository for T where
+    T: AccountRepository
+        + AuditRepository
+        + ContentRepository
+        + CourseRepository
+        + EnrollmentRepository
+        + ReviewRepository
+        + StudentRepository
+        + TeacherRepository
+        + TrashRepository
+        + UnitOfWorkRepository
+        + SchemaMigrator
+        + Send
+        + Sync
+{
+}
+
+/// 写入 created_at、updated_at、deleted_at 等列的当前时间（UTC），精确到秒，保证两种数据库保存的值与比较时使用的值一致
+pub(crate) fn db_now() -> NaiveDateTime {
+    Utc::now().naive_utc().trunc_subsecs(0)
+}
+
+/// 按版本号写入时记录已被修改（或已删除）的错误，entity 如 "Course"
+pub(crate) fn version_mismatch(entity: &str) -> MyError {
+    MyError::PreconditionFailed(format!("{} has been modified; fetch it again and retry", entity))
+}
+
+/// 根据 DATABASE_URL 的协议创建对应的连接池，支持 `mysql://` 和 `sqlite:`
+pub async fn connect(db_url: &str) -> Result<Box<dyn Repository>, sqlx::Error> {
+    if db_url.starts_with("mysql:") {
+        let pool = MySqlPoolOptions::new().connect(db_url).await?;
+
+        Ok(Box::new(pool))
+    } else if db_url.starts_with("sqlite:") {
+        let options = SqliteConnectOptions::from_str(db_url)?.create_if_missing(true);
+        let mut pool_options = SqlitePoolOptions::new();
+        if db_url.contains(":memory:") || db_url.contains("mode=memory") {
+            // 内存数据库只存在于创建它的连接中，连接池里只能保留这一个连接
+            pool_options = pool_options
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+        let pool = pool_options.connect_with(options).await?;
+
+        Ok(Box::new(pool))
+    } else {
+        Err(sqlx::Error::Configuration(
+            format!("Unsupported DATABASE_URL: {}", db_url).into(),
+        ))
+    }
+}
@@ -0,0 +1,37 @@
+use sqlx::mysql::MySqlQueryResult;
+use sqlx::{MySql, QueryBuilder};
+use super::sql::Dialect;
+
+impl Dialect for MySql {
+    const FOR_UPDATE: &'static str = " FOR UPDATE";
+
+    const INSERT_IGNORE: &'static str = "INSERT IGNORE";
+
+    fn last_insert_id(result: &MySqlQueryResult) -> i32 {
+        result.last_insert_id() as i32
+    }
+
+    fn push_search_hits(builder: &mut QueryBuilder<'_, MySql>, q: &str) {
+        builder.push(" JOIN (SELECT id, MAX(score) AS score FROM (SELECT id, ");
+        push_match(builder, "name, description", q);
+        builder.push(" AS score FROM course WHERE ");
+        push_match(builder, "name, description", q);
+        builder.push(" UNION ALL SELECT course_id, ");
+        push_match(builder, "title", q);
+        builder.push(" FROM course_module WHERE ");
+        push_match(builder, "title", q);
+        builder.push(" UNION ALL SELECT course_module.course_id, ");
+        push_match(builder, "lesson.title", q);
+        builder.push(" FROM lesson JOIN course_module ON course_module.id = lesson.module_id WHERE ");
+        push_match(builder, "lesson.title", q);
+        builder.push(") AS matches GROUP BY id) AS hits ON hits.id = course.id");
+    }
+}
+
+/// 自然语言模式的全文匹配，返回值就是相关度
+fn push_match(builder: &mut QueryBuilder<'_, MySql>, columns: &str, q: &str) {
+    builder
+        .push(format!("MATCH ({}) AGAINST (", columns))
+        .push_bind(q.to_string())
+        .push(" IN NATURAL LANGUAGE MODE)");
+}
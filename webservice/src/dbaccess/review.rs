@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use crate::errors::MyError;
+use crate::models::review::{CreateReview, RatingSummary, Review, ReviewQuery, UpdateReview};
+use crate::models::audit::AuditContext;
+
+#[async_trait]
+pub trait ReviewRepository {
+    /// 只有已选课（不含候补）的学生可以评价，每门课程只能评价一次，重复评价返回 Conflict
+    async fn post_new_review_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        student_id: i32,
+        new_review: CreateReview,
+    ) -> Result<Review, MyError>;
+
+    /// 没有匹配的评价时返回 NotFound
+    async fn get_review_db(&self, teacher_id: i32, course_id: i32, review_id: i32) -> Result<Review, MyError>;
+
+    async fn update_review_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        review_id: i32,
+        update_review: UpdateReview,
+    ) -> Result<Review, MyError>;
+
+    /// 没有匹配的评价时返回 NotFound
+    async fn delete_review_db(
+        &self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        review_id: i32,
+    ) -> Result<(), MyError>;
+
+    /// 返回当前页的评价和课程的评价总数，课程不存在时返回 NotFound
+    async fn get_reviews_for_course_db(
+        &self,
+        teacher_id: i32,
+        course_id: i32,
+        query: &ReviewQuery,
+    ) -> Result<(Vec<Review>, i64), MyError>;
+
+    /// 教师全部课程的评价汇总
+    async fn get_teacher_rating_db(&self, teacher_id: i32) -> Result<RatingSummary, MyError>;
+}
+
+/// 查询评价时共用的列和连接，MySQL 和 SQLite 共用
+pub(crate) const REVIEW_SELECT: &str = "SELECT review.id, review.course_id, review.student_id, student.name AS student_name,
+        review.rating, review.comment, review.created_at, review.updated_at
+    FROM review
+    JOIN student ON student.id = review.student_id
+    JOIN course ON course.id = review.course_id AND course.deleted_at IS NULL";
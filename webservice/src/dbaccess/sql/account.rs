@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, Sqlite, Transaction};
+use serde_json::json;
+use crate::dbaccess::account::{parse_roles, AccountRepository};
+use crate::dbaccess::db_now;
+use crate::errors::MyError;
+use crate::models::auth::{Account, Role};
+use crate::models::teacher::{CreateTeacher, Teacher};
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 AccountRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! account_repository {
+    ($module:ident, $db:ty) => {
+        mod $module {
+            use super::*;
+            use super::super::audit::$module::insert_audit;
+            use super::super::teacher::$module::find_teacher;
+
+            #[async_trait]
+            impl AccountRepository for Pool<$db> {
+                async fn register_teacher_db(
+                    &self,
+                    ctx: &AuditContext,
+                    new_teacher: CreateTeacher,
+                    username: &str,
+                    password_hash: &str,
+                ) -> Result<Teacher, MyError> {
+                    let mut tx = self.begin().await?;
+
+                    let now = db_now();
+                    let teacher_row = sqlx::query(
+                        "INSERT INTO teacher (name, picture_url, profile, created_at, updated_at)
+                            VALUES (?, ?, ?, ?, ?)"
+                    )
+                        .bind(new_teacher.name)
+                        .bind(new_teacher.picture_url)
+                        .bind(new_teacher.profile)
+                        .bind(now)
+                        .bind(now)
+                        .execute(&mut tx)
+                        .await?;
+                    let teacher_id = <$db>::last_insert_id(&teacher_row);
+
+                    let account_row = sqlx::query(
+                        "INSERT INTO account (username, password_hash, teacher_id)
+                            VALUES (?, ?, ?)"
+                    )
+                        .bind(username)
+                        .bind(password_hash)
+                        .bind(teacher_id)
+                        .execute(&mut tx)
+                        .await?;
+
+                    let account_id = <$db>::last_insert_id(&account_row);
+
+                    sqlx::query(
+                        "INSERT INTO account_role (account_id, role)
+                            VALUES (?, ?)"
+                    )
+                        .bind(account_id)
+                        .bind(Role::Teacher.as_str())
+                        .execute(&mut tx)
+                        .await?;
+
+                    let teacher = find_teacher(&mut tx, teacher_id).await?;
+                    insert_audit(&mut tx, ctx, AuditRecord::created(AuditEntity::Teacher, teacher_id, &teacher)).await?;
+                    // 审计日志中不记录密码哈希
+                    let account = json!({ "username": username, "teacher_id": teacher_id, "roles": [Role::Teacher] });
+                    insert_audit(&mut tx, ctx, AuditRecord::created(AuditEntity::Account, account_id, &account)).await?;
+
+                    tx.commit().await?;
+
+                    Ok(teacher)
+                }
+
+                async fn get_account_by_username_db(&self, username: &str) -> Result<Account, MyError> {
+                    sqlx::query_as(
+                        "SELECT account.id, account.username, account.password_hash, account.teacher_id,
+                                    student.id AS student_id
+                                FROM account
+                                LEFT JOIN student ON student.account_id = account.id
+                                WHERE account.username = ?"
+                    )
+                        .bind(username)
+                        .fetch_optional(self)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("account", "Account not found"))
+                }
+
+                async fn get_account_db(&self, account_id: i32) -> Result<Account, MyError> {
+                    find_account(self, account_id).await
+                }
+
+                async fn get_account_roles_db(&self, account_id: i32) -> Result<Vec<Role>, MyError> {
+                    find_roles(self, account_id).await
+                }
+
+                async fn grant_role_db(&self, ctx: &AuditContext, account_id: i32, role: Role) -> Result<Vec<Role>, MyError> {
+                    let mut tx = self.begin().await?;
+
+                    find_account(&mut tx, account_id).await?;
+                    let current_roles = find_roles(&mut tx, account_id).await?;
+
+                    sqlx::query(&format!(
+                        "{} INTO account_role (account_id, role)
+                            VALUES (?, ?)",
+                        <$db>::INSERT_IGNORE
+                    ))
+                        .bind(account_id)
+                        .bind(role.as_str())
+                        .execute(&mut tx)
+                        .await?;
+
+                    let roles = find_roles(&mut tx, account_id).await?;
+                    record_role_change(&mut tx, ctx, account_id, &current_roles, &roles).await?;
+
+                    tx.commit().await?;
+
+                    Ok(roles)
+                }
+
+                async fn revoke_role_db(&self, ctx: &AuditContext, account_id: i32, role: Role) -> Result<Vec<Role>, MyError> {
+                    let mut tx = self.begin().await?;
+
+                    find_account(&mut tx, account_id).await?;
+                    let current_roles = find_roles(&mut tx, account_id).await?;
+
+                    sqlx::query(
+                        "DELETE FROM account_role
+                            WHERE account_id = ? AND role = ?"
+                    )
+                        .bind(account_id)
+                        .bind(role.as_str())
+                        .execute(&mut tx)
+                        .await?;
+
+                    let roles = find_roles(&mut tx, account_id).await?;
+                    record_role_change(&mut tx, ctx, account_id, &current_roles, &roles).await?;
+
+                    tx.commit().await?;
+
+                    Ok(roles)
+                }
+            }
+
+            async fn find_account<'e, E>(executor: E, account_id: i32) -> Result<Account, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query_as(
+                    "SELECT account.id, account.username, account.password_hash, account.teacher_id,
+                                student.id AS student_id
+                            FROM account
+                            LEFT JOIN student ON student.account_id = account.id
+                            WHERE account.id = ?"
+                )
+                    .bind(account_id)
+                    .fetch_optional(executor)
+                    .await?
+                    .ok_or_else(|| MyError::not_found("account", "Account not found"))
+            }
+
+            async fn find_roles<'e, E>(executor: E, account_id: i32) -> Result<Vec<Role>, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                let rows: Vec<(String,)> = sqlx::query_as(
+                    "SELECT role
+                            FROM account_role
+                            WHERE account_id = ?
+                            ORDER BY role"
+                )
+                    .bind(account_id)
+                    .fetch_all(executor)
+                    .await?;
+
+                parse_roles(rows)
+            }
+
+            /// 角色确实发生变化时记录一条账号的修改
+            async fn record_role_change(
+                tx: &mut Transaction<'_, $db>,
+                ctx: &AuditContext,
+                account_id: i32,
+                before: &[Role],
+                after: &[Role],
+            ) -> Result<(), MyError> {
+                if before == after {
+                    return Ok(());
+                }
+                insert_audit(
+                    tx,
+                    ctx,
+                    AuditRecord::updated(
+                        AuditEntity::Account,
+                        account_id,
+                        &json!({ "roles": before }),
+                        &json!({ "roles": after }),
+                    ),
+                )
+                .await
+            }
+        }
+    };
+}
+
+account_repository!(mysql, MySql);
+account_repository!(sqlite, Sqlite);
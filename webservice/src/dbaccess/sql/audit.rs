@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, QueryBuilder, Sqlite};
+use crate::dbaccess::audit::{push_audit_filters, AuditRepository, AuditRow};
+use crate::dbaccess::db_now;
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditEntry, AuditQuery, AuditRecord};
+
+/// 生成 MySQL 或 SQLite 的 AuditRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! audit_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+
+            #[async_trait]
+            impl AuditRepository for Pool<$db> {
+                async fn get_audit_log_db(&self, query: &AuditQuery) -> Result<(Vec<AuditEntry>, i64), MyError> {
+                    let entity = query.entity()?;
+
+                    let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM audit_log WHERE 1 = 1");
+                    push_audit_filters(&mut count_query, entity, query.id);
+                    let (total,): (i64,) = count_query
+                        .build_query_as()
+                        .fetch_one(self)
+                        .await?;
+
+                    let mut select_query = QueryBuilder::new("SELECT * FROM audit_log WHERE 1 = 1");
+                    push_audit_filters(&mut select_query, entity, query.id);
+                    select_query
+                        .push(" ORDER BY id DESC LIMIT ")
+                        .push_bind(query.limit())
+                        .push(" OFFSET ")
+                        .push_bind(query.offset());
+                    let rows: Vec<AuditRow> = select_query
+                        .build_query_as()
+                        .fetch_all(self)
+                        .await?;
+
+                    let entries = rows
+                        .into_iter()
+                        .map(AuditEntry::try_from)
+                        .collect::<Result<Vec<AuditEntry>, MyError>>()?;
+
+                    Ok((entries, total))
+                }
+            }
+
+            /// 在事务中写入一条审计记录
+            pub(crate) async fn insert_audit<'e, E>(executor: E, ctx: &AuditContext, record: AuditRecord) -> Result<(), MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query(
+                    "INSERT INTO audit_log (actor_id, action, entity, entity_id, old_values, new_values, request_id, created_at)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+                )
+                    .bind(ctx.actor_id)
+                    .bind(record.action.as_str())
+                    .bind(record.entity.as_str())
+                    .bind(record.entity_id)
+                    .bind(record.before.map(|before| before.to_string()))
+                    .bind(record.after.map(|after| after.to_string()))
+                    .bind(&ctx.request_id)
+                    .bind(db_now())
+                    .execute(executor)
+                    .await?;
+
+                Ok(())
+            }
+        }
+    };
+}
+
+audit_repository!(mysql, MySql);
+audit_repository!(sqlite, Sqlite);
@@ -0,0 +1,443 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, Sqlite, Transaction};
+use crate::dbaccess::content::{apply_lesson_changes, check_order, module_outlines, position_changes, ContentRepository};
+use crate::dbaccess::unit_of_work::UnitOfWork;
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use crate::models::content::{
+    CourseModule, CreateLesson, CreateModule, Lesson, ModuleOutline, UpdateLesson, UpdateModule,
+};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 ContentRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! content_repository {
+    ($module:ident, $db:ty) => {
+        mod $module {
+            use super::*;
+            use super::super::course::$module::ensure_course_exists;
+            use super::super::unit_of_work::$module::SqlUnitOfWork;
+
+            #[async_trait]
+            impl ContentRepository for Pool<$db> {
+                async fn get_course_modules_db(&self, teacher_id: i32, course_id: i32) -> Result<Vec<ModuleOutline>, MyError> {
+                    ensure_course_exists(self, teacher_id, course_id).await?;
+
+                    let modules: Vec<CourseModule> = sqlx::query_as(
+                        "SELECT * FROM course_module
+                            WHERE course_id = ?
+                            ORDER BY position, id"
+                    )
+                        .bind(course_id)
+                        .fetch_all(self)
+                        .await?;
+
+                    let lessons: Vec<Lesson> = sqlx::query_as(
+                        "SELECT lesson.* FROM lesson
+                            JOIN course_module ON course_module.id = lesson.module_id
+                            WHERE course_module.course_id = ?
+                            ORDER BY lesson.position, lesson.id"
+                    )
+                        .bind(course_id)
+                        .fetch_all(self)
+                        .await?;
+
+                    Ok(module_outlines(modules, lessons))
+                }
+
+                async fn get_module_db(&self, teacher_id: i32, course_id: i32, module_id: i32) -> Result<ModuleOutline, MyError> {
+                    let module = find_module(self, teacher_id, course_id, module_id).await?;
+
+                    let lessons: Vec<Lesson> = sqlx::query_as(
+                        "SELECT * FROM lesson
+                            WHERE module_id = ?
+                            ORDER BY position, id"
+                    )
+                        .bind(module_id)
+                        .fetch_all(self)
+                        .await?;
+
+                    Ok(ModuleOutline::new(module, lessons))
+                }
+
+                async fn post_new_module_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    new_module: CreateModule,
+                ) -> Result<CourseModule, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    // 锁定课程行，同一课程新增的章节依次取得位置，不会得到相同的 position
+                    uow.lock_course_db(teacher_id, course_id).await?;
+
+                    let row = sqlx::query(
+                        "INSERT INTO course_module (course_id, title, position)
+                            SELECT ?, ?, COALESCE(MAX(position), 0) + 1
+                            FROM course_module
+                            WHERE course_id = ?"
+                    )
+                        .bind(course_id)
+                        .bind(new_module.title)
+                        .bind(course_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let module = find_module(&mut uow.tx, teacher_id, course_id, <$db>::last_insert_id(&row)).await?;
+                    uow.record_audit_db(ctx, AuditRecord::created(AuditEntity::Module, module.id, &module)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(module)
+                }
+
+                async fn update_module_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                    update_module: UpdateModule,
+                ) -> Result<CourseModule, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let current_module_row = find_module(&mut uow.tx, teacher_id, course_id, module_id).await?;
+
+                    let row = sqlx::query(
+                        "UPDATE course_module
+                            SET title = ?
+                            WHERE id = ?"
+                    )
+                        .bind(update_module.title.unwrap_or_else(|| current_module_row.title.clone()))
+                        .bind(module_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("module", "Module Id not found"));
+                    }
+
+                    let module = find_module(&mut uow.tx, teacher_id, course_id, module_id).await?;
+                    uow.record_audit_db(
+                        ctx,
+                        AuditRecord::updated(AuditEntity::Module, module_id, &current_module_row, &module),
+                    )
+                    .await?;
+
+                    uow.commit().await?;
+
+                    Ok(module)
+                }
+
+                async fn delete_module_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                ) -> Result<(), MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let module = find_module(&mut uow.tx, teacher_id, course_id, module_id).await?;
+
+                    let row = sqlx::query(
+                        "DELETE FROM course_module
+                            WHERE id = ?"
+                    )
+                        .bind(module_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("module", "Module Id not found"));
+                    }
+
+                    uow.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Module, module_id, &module)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(())
+                }
+
+                async fn reorder_modules_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_ids: &[i32],
+                ) -> Result<Vec<ModuleOutline>, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    uow.lock_course_db(teacher_id, course_id).await?;
+
+                    let existing: Vec<(i32, i32)> = sqlx::query_as(&format!(
+                        "SELECT id, position FROM course_module
+                            WHERE course_id = ?{}",
+                        <$db>::FOR_UPDATE
+                    ))
+                        .bind(course_id)
+                        .fetch_all(&mut uow.tx)
+                        .await?;
+                    let existing_ids: Vec<i32> = existing.iter().map(|(id, _position)| *id).collect();
+                    check_order(&existing_ids, module_ids)?;
+
+                    for (position, module_id) in module_ids.iter().enumerate() {
+                        sqlx::query(
+                            "UPDATE course_module
+                                SET position = ?
+                                WHERE id = ?"
+                        )
+                            .bind(position as i32 + 1)
+                            .bind(module_id)
+                            .execute(&mut uow.tx)
+                            .await?;
+                    }
+
+                    for record in position_changes(AuditEntity::Module, &existing, module_ids) {
+                        uow.record_audit_db(ctx, record).await?;
+                    }
+
+                    uow.commit().await?;
+
+                    self.get_course_modules_db(teacher_id, course_id).await
+                }
+
+                async fn post_new_lesson_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                    new_lesson: CreateLesson,
+                ) -> Result<Lesson, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    // 锁定章节行，同一章节新增的课时依次取得位置
+                    lock_module(&mut uow.tx, teacher_id, course_id, module_id).await?;
+
+                    let row = sqlx::query(
+                        "INSERT INTO lesson (module_id, title, body, estimated_minutes, attachment_url, position)
+                            SELECT ?, ?, ?, ?, ?, COALESCE(MAX(position), 0) + 1
+                            FROM lesson
+                            WHERE module_id = ?"
+                    )
+                        .bind(module_id)
+                        .bind(new_lesson.title)
+                        .bind(new_lesson.body)
+                        .bind(new_lesson.estimated_minutes)
+                        .bind(new_lesson.attachment_url)
+                        .bind(module_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let lesson = find_lesson(&mut uow.tx, teacher_id, course_id, module_id, <$db>::last_insert_id(&row)).await?;
+                    uow.record_audit_db(ctx, AuditRecord::created(AuditEntity::Lesson, lesson.id, &lesson)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(lesson)
+                }
+
+                async fn update_lesson_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                    lesson_id: i32,
+                    update_lesson: UpdateLesson,
+                ) -> Result<Lesson, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let current_lesson_row = find_lesson(&mut uow.tx, teacher_id, course_id, module_id, lesson_id).await?;
+                    let lesson = apply_lesson_changes(current_lesson_row.clone(), update_lesson);
+
+                    let row = sqlx::query(
+                        "UPDATE lesson
+                            SET title = ?, body = ?, estimated_minutes = ?, attachment_url = ?
+                            WHERE id = ?"
+                    )
+                        .bind(&lesson.title)
+                        .bind(&lesson.body)
+                        .bind(lesson.estimated_minutes)
+                        .bind(&lesson.attachment_url)
+                        .bind(lesson_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("lesson", "Lesson Id not found"));
+                    }
+
+                    uow.record_audit_db(
+                        ctx,
+                        AuditRecord::updated(AuditEntity::Lesson, lesson_id, &current_lesson_row, &lesson),
+                    )
+                    .await?;
+
+                    uow.commit().await?;
+
+                    Ok(lesson)
+                }
+
+                async fn delete_lesson_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                    lesson_id: i32,
+                ) -> Result<(), MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let lesson = find_lesson(&mut uow.tx, teacher_id, course_id, module_id, lesson_id).await?;
+
+                    let row = sqlx::query(
+                        "DELETE FROM lesson
+                            WHERE id = ?"
+                    )
+                        .bind(lesson_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("lesson", "Lesson Id not found"));
+                    }
+
+                    uow.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Lesson, lesson_id, &lesson)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(())
+                }
+
+                async fn reorder_lessons_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    module_id: i32,
+                    lesson_ids: &[i32],
+                ) -> Result<ModuleOutline, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    lock_module(&mut uow.tx, teacher_id, course_id, module_id).await?;
+
+                    let existing: Vec<(i32, i32)> = sqlx::query_as(&format!(
+                        "SELECT id, position FROM lesson
+                            WHERE module_id = ?{}",
+                        <$db>::FOR_UPDATE
+                    ))
+                        .bind(module_id)
+                        .fetch_all(&mut uow.tx)
+                        .await?;
+                    let existing_ids: Vec<i32> = existing.iter().map(|(id, _position)| *id).collect();
+                    check_order(&existing_ids, lesson_ids)?;
+
+                    for (position, lesson_id) in lesson_ids.iter().enumerate() {
+                        sqlx::query(
+                            "UPDATE lesson
+                                SET position = ?
+                                WHERE id = ?"
+                        )
+                            .bind(position as i32 + 1)
+                            .bind(lesson_id)
+                            .execute(&mut uow.tx)
+                            .await?;
+                    }
+
+                    for record in position_changes(AuditEntity::Lesson, &existing, lesson_ids) {
+                        uow.record_audit_db(ctx, record).await?;
+                    }
+
+                    uow.commit().await?;
+
+                    self.get_module_db(teacher_id, course_id, module_id).await
+                }
+            }
+
+            /// 章节必须属于该教师的该课程，否则返回 NotFound
+            async fn find_module<'e, E>(
+                executor: E,
+                teacher_id: i32,
+                course_id: i32,
+                module_id: i32,
+            ) -> Result<CourseModule, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query_as(
+                    "SELECT course_module.* FROM course_module
+                        JOIN course ON course.id = course_module.course_id AND course.deleted_at IS NULL
+                        WHERE course.teacher_id = ? AND course_module.course_id = ? AND course_module.id = ?"
+                )
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .bind(module_id)
+                    .fetch_optional(executor)
+                    .await?
+                    .ok_or_else(|| MyError::not_found("module", "Module Id not found"))
+            }
+
+            /// 锁定章节行，章节不属于该教师的该课程时返回 NotFound
+            async fn lock_module(
+                tx: &mut Transaction<'_, $db>,
+                teacher_id: i32,
+                course_id: i32,
+                module_id: i32,
+            ) -> Result<(), MyError> {
+                if <$db>::FOR_UPDATE.is_empty() {
+                    sqlx::query("UPDATE course_module SET position = position WHERE id = ?")
+                        .bind(module_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                let module: Option<(i32,)> = sqlx::query_as(&format!(
+                    "SELECT course_module.id FROM course_module
+                        JOIN course ON course.id = course_module.course_id AND course.deleted_at IS NULL
+                        WHERE course.teacher_id = ? AND course_module.course_id = ? AND course_module.id = ?{}",
+                    <$db>::FOR_UPDATE
+                ))
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .bind(module_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+                module
+                    .map(|_| ())
+                    .ok_or_else(|| MyError::not_found("module", "Module Id not found"))
+            }
+
+            /// 课时必须属于该课程的该章节，否则返回 NotFound
+            async fn find_lesson<'e, E>(
+                executor: E,
+                teacher_id: i32,
+                course_id: i32,
+                module_id: i32,
+                lesson_id: i32,
+            ) -> Result<Lesson, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query_as(
+                    "SELECT lesson.* FROM lesson
+                        JOIN course_module ON course_module.id = lesson.module_id
+                        JOIN course ON course.id = course_module.course_id AND course.deleted_at IS NULL
+                        WHERE course.teacher_id = ? AND course_module.course_id = ? AND lesson.module_id = ? AND lesson.id = ?"
+                )
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .bind(module_id)
+                    .bind(lesson_id)
+                    .fetch_optional(executor)
+                    .await?
+                    .ok_or_else(|| MyError::not_found("lesson", "Lesson Id not found"))
+            }
+        }
+    };
+}
+
+content_repository!(mysql, MySql);
+content_repository!(sqlite, Sqlite);
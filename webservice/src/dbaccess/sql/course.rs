@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use crate::dbaccess::course::{push_course_filters, CourseRepository};
+use crate::dbaccess::unit_of_work::UnitOfWorkRepository;
+use crate::models::course::{Course, CourseQuery, CreateCourse, UpdateCourse};
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use sqlx::{Executor, MySql, Pool, QueryBuilder, Sqlite};
+use super::Dialect;
+
+/// 课程的全部列、由课时计算的总时长以及评价汇总，查询课程的语句都从这里取列；
+/// MySQL 的 SUM 和 AVG 返回 DECIMAL 需要转换，SQLite 把 SIGNED 和 DOUBLE 分别当作整数和浮点数
+pub(super) const COURSE_COLUMNS: &str = "course.*,
+    (SELECT CAST(COALESCE(SUM(lesson.estimated_minutes), 0) AS SIGNED) FROM lesson
+        JOIN course_module ON course_module.id = lesson.module_id
+        WHERE course_module.course_id = course.id) AS duration,
+    (SELECT CAST(AVG(review.rating) AS DOUBLE) FROM review WHERE review.course_id = course.id) AS avg_rating,
+    (SELECT COUNT(*) FROM review WHERE review.course_id = course.id) AS review_count";
+
+/// 生成 MySQL 或 SQLite 的 CourseRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! course_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+
+            #[async_trait]
+            impl CourseRepository for Pool<$db> {
+                async fn post_new_course_db(&self, ctx: &AuditContext, new_course: CreateCourse) -> Result<Course, MyError> {
+                    let mut uow = self.begin_db().await?;
+                    let course = uow.post_new_course_db(ctx, new_course).await?;
+                    uow.commit().await?;
+
+                    Ok(course)
+                }
+
+                async fn delete_course_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    expected_version: Option<i32>,
+                ) -> Result<(), MyError> {
+                    let mut uow = self.begin_db().await?;
+                    uow.delete_course_db(ctx, teacher_id, course_id, expected_version).await?;
+                    uow.commit().await
+                }
+
+                async fn update_course_details_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    update_course: UpdateCourse,
+                    expected_version: Option<i32>,
+                ) -> Result<Course, MyError> {
+                    let mut uow = self.begin_db().await?;
+                    let course = uow
+                        .update_course_details_db(ctx, teacher_id, course_id, update_course, expected_version)
+                        .await?;
+                    uow.commit().await?;
+
+                    Ok(course)
+                }
+
+                async fn get_courses_for_teacher_db(
+                    &self,
+                    teacher_id: i32,
+                    query: &CourseQuery,
+                ) -> Result<(Vec<Course>, i64), MyError> {
+                    select_courses(self, Some(teacher_id), query).await
+                }
+
+                async fn search_courses_db(&self, query: &CourseQuery) -> Result<(Vec<Course>, i64), MyError> {
+                    select_courses(self, None, query).await
+                }
+
+                async fn get_course_details_db(&self, teacher_id: i32, course_id: i32) -> Result<Course, MyError> {
+                    find_course(self, teacher_id, course_id).await
+                }
+            }
+
+            /// 返回该教师未删除的课程，不存在时返回 NotFound；连接池和事务共用
+            pub(crate) async fn find_course<'e, E>(executor: E, teacher_id: i32, course_id: i32) -> Result<Course, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                let row = sqlx::query_as(&format!(
+                    "SELECT {} FROM course
+                        WHERE teacher_id = ? AND id = ? AND deleted_at IS NULL",
+                    COURSE_COLUMNS
+                ))
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .fetch_optional(executor) // 获取单条记录
+                    .await?;
+
+                if let Some(course) = row {
+                    Ok(course)
+                } else {
+                    Err(MyError::not_found("course", "Course Id not found"))
+                }
+            }
+
+            /// 课程不存在时返回 NotFound
+            pub(crate) async fn ensure_course_exists<'e, E>(executor: E, teacher_id: i32, course_id: i32) -> Result<(), MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                let course: Option<(i32,)> = sqlx::query_as(
+                    "SELECT id FROM course
+                        WHERE teacher_id = ? AND id = ? AND deleted_at IS NULL"
+                )
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .fetch_optional(executor)
+                    .await?;
+
+                course
+                    .map(|_| ())
+                    .ok_or_else(|| MyError::not_found("course", "Course Id not found"))
+            }
+
+            /// 课程列表和目录检索共用的 FROM 和 WHERE 子句，teacher_id 为 None 时不限制教师
+            fn push_course_source(builder: &mut QueryBuilder<'_, $db>, teacher_id: Option<i32>, query: &CourseQuery) {
+                builder.push(" FROM course");
+                if let Some(q) = query.search_text() {
+                    <$db>::push_search_hits(builder, q);
+                }
+                builder.push(" WHERE course.deleted_at IS NULL");
+                if let Some(teacher_id) = teacher_id {
+                    builder.push(" AND teacher_id = ").push_bind(teacher_id);
+                }
+                push_course_filters(builder, query);
+            }
+
+            async fn select_courses(
+                pool: &Pool<$db>,
+                teacher_id: Option<i32>,
+                query: &CourseQuery,
+            ) -> Result<(Vec<Course>, i64), MyError> {
+                let mut count_query = QueryBuilder::new("SELECT COUNT(*)");
+                push_course_source(&mut count_query, teacher_id, query);
+                let (total,): (i64,) = count_query
+                    .build_query_as()
+                    .fetch_one(pool)
+                    .await?;
+
+                let mut select_query = QueryBuilder::new(format!("SELECT {}", COURSE_COLUMNS));
+                push_course_source(&mut select_query, teacher_id, query);
+                let order_by = match (query.search_text(), &query.sort) {
+                    (Some(_), None) => "hits.score DESC, course.id",
+                    _ => query.order_by()?,
+                };
+                select_query
+                    .push(" ORDER BY ")
+                    .push(order_by)
+                    .push(" LIMIT ")
+                    .push_bind(query.limit())
+                    .push(" OFFSET ")
+                    .push_bind(query.offset());
+                let rows: Vec<Course> = select_query
+                    .build_query_as()
+                    .fetch_all(pool) // 获取当前页的记录
+                    .await?;
+
+                Ok((rows, total))
+            }
+        }
+    };
+}
+
+course_repository!(mysql, MySql);
+course_repository!(sqlite, Sqlite);
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool, Sqlite, Transaction};
+use serde_json::json;
+use crate::dbaccess::db_now;
+use crate::dbaccess::enrollment::{enrollment_status, open_seats, EnrollmentRepository};
+use crate::dbaccess::student::StudentRepository;
+use crate::dbaccess::unit_of_work::UnitOfWork;
+use crate::errors::MyError;
+use crate::models::enrollment::{CourseRoster, Enrollment, EnrollmentStatus, RosterEntry, StudentCourse};
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use super::course::COURSE_COLUMNS;
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 EnrollmentRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! enrollment_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+            use super::super::unit_of_work::$module::SqlUnitOfWork;
+
+            #[async_trait]
+            impl EnrollmentRepository for Pool<$db> {
+                async fn enroll_student_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    student_id: i32,
+                ) -> Result<Enrollment, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    // 锁定课程行，同一课程的选课、退课和修改容量依次执行，避免超出容量
+                    let capacity = uow.lock_course_db(teacher_id, course_id).await?.capacity;
+
+                    let existing: Option<(i32,)> = sqlx::query_as(
+                        "SELECT id FROM enrollment
+                            WHERE course_id = ? AND student_id = ?"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .fetch_optional(&mut uow.tx)
+                        .await?;
+                    if existing.is_some() {
+                        return Err(MyError::Conflict("Student is already enrolled in this course".into()));
+                    }
+
+                    let enrolled = count_enrolled(&mut uow.tx, course_id).await?;
+                    let row = sqlx::query(
+                        "INSERT INTO enrollment (course_id, student_id, status, enrolled_at)
+                            VALUES (?, ?, ?, ?)"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .bind(enrollment_status(capacity, enrolled).as_str())
+                        .bind(db_now())
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let enrollment: Enrollment = sqlx::query_as(
+                        "SELECT * FROM enrollment
+                            WHERE id = ?"
+                    )
+                        .bind(<$db>::last_insert_id(&row))
+                        .fetch_one(&mut uow.tx)
+                        .await?;
+
+                    uow.record_audit_db(
+                        ctx,
+                        AuditRecord::created(AuditEntity::Enrollment, enrollment.id, &enrollment),
+                    )
+                    .await?;
+
+                    uow.commit().await?;
+
+                    Ok(enrollment)
+                }
+
+                async fn withdraw_student_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    student_id: i32,
+                ) -> Result<(), MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let capacity = uow.lock_course_db(teacher_id, course_id).await?.capacity;
+
+                    let enrollment: Enrollment = sqlx::query_as(
+                        "SELECT * FROM enrollment
+                            WHERE course_id = ? AND student_id = ?"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .fetch_optional(&mut uow.tx)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("enrollment", "Enrollment not found"))?;
+
+                    sqlx::query(
+                        "DELETE FROM enrollment
+                            WHERE id = ?"
+                    )
+                        .bind(enrollment.id)
+                        .execute(&mut uow.tx)
+                        .await?;
+                    let mut records = vec![AuditRecord::deleted(AuditEntity::Enrollment, enrollment.id, &enrollment)];
+
+                    // 空出的名额按候补顺序递补
+                    records.extend(promote_waitlist(&mut uow.tx, course_id, capacity).await?);
+
+                    for record in records {
+                        uow.record_audit_db(ctx, record).await?;
+                    }
+
+                    uow.commit().await?;
+
+                    Ok(())
+                }
+
+                async fn get_course_roster_db(&self, teacher_id: i32, course_id: i32) -> Result<CourseRoster, MyError> {
+                    let (capacity,): (Option<i32>,) = sqlx::query_as(
+                        "SELECT capacity FROM course
+                            WHERE teacher_id = ? AND id = ? AND deleted_at IS NULL"
+                    )
+                        .bind(teacher_id)
+                        .bind(course_id)
+                        .fetch_optional(self)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("course", "Course Id not found"))?;
+
+                    let entries: Vec<RosterEntry> = sqlx::query_as(
+                        "SELECT enrollment.student_id, student.name, student.email, enrollment.status, enrollment.enrolled_at
+                            FROM enrollment
+                            JOIN student ON student.id = enrollment.student_id
+                            WHERE enrollment.course_id = ?
+                            ORDER BY enrollment.enrolled_at, enrollment.id"
+                    )
+                        .bind(course_id)
+                        .fetch_all(self)
+                        .await?;
+
+                    let (enrolled, waitlist) = entries
+                        .into_iter()
+                        .partition(|entry| entry.status == EnrollmentStatus::Enrolled);
+
+                    Ok(CourseRoster {
+                        course_id,
+                        capacity,
+                        enrolled,
+                        waitlist,
+                    })
+                }
+
+                async fn get_student_courses_db(&self, student_id: i32) -> Result<Vec<StudentCourse>, MyError> {
+                    self.get_student_details_db(student_id).await?;
+
+                    let courses = sqlx::query_as(&format!(
+                        "SELECT {}, enrollment.status, enrollment.enrolled_at
+                            FROM enrollment
+                            JOIN course ON course.id = enrollment.course_id AND course.deleted_at IS NULL
+                            WHERE enrollment.student_id = ?
+                            ORDER BY enrollment.enrolled_at, enrollment.id",
+                        COURSE_COLUMNS
+                    ))
+                        .bind(student_id)
+                        .fetch_all(self)
+                        .await?;
+
+                    Ok(courses)
+                }
+            }
+
+            /// 按候补顺序把容量允许的候补学生转为已选，返回每个递补学生的审计记录；
+            /// 调用方须已锁定课程行
+            pub(crate) async fn promote_waitlist(
+                tx: &mut Transaction<'_, $db>,
+                course_id: i32,
+                capacity: Option<i32>,
+            ) -> Result<Vec<AuditRecord>, MyError> {
+                let enrolled = count_enrolled(tx, course_id).await?;
+                let mut promote_query = sqlx::QueryBuilder::new(
+                    "SELECT id FROM enrollment WHERE course_id = "
+                );
+                promote_query
+                    .push_bind(course_id)
+                    .push(" AND status = ")
+                    .push_bind(EnrollmentStatus::Waitlisted.as_str())
+                    .push(" ORDER BY enrolled_at, id");
+                if let Some(seats) = open_seats(capacity, enrolled) {
+                    promote_query.push(" LIMIT ").push_bind(seats);
+                }
+                let promoted: Vec<(i32,)> = promote_query
+                    .build_query_as()
+                    .fetch_all(&mut *tx)
+                    .await?;
+
+                let mut records = Vec::new();
+                for (enrollment_id,) in promoted {
+                    sqlx::query(
+                        "UPDATE enrollment
+                            SET status = ?
+                            WHERE id = ?"
+                    )
+                        .bind(EnrollmentStatus::Enrolled.as_str())
+                        .bind(enrollment_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    records.push(AuditRecord::updated(
+                        AuditEntity::Enrollment,
+                        enrollment_id,
+                        &json!({ "status": EnrollmentStatus::Waitlisted }),
+                        &json!({ "status": EnrollmentStatus::Enrolled }),
+                    ));
+                }
+
+                Ok(records)
+            }
+
+            async fn count_enrolled(tx: &mut Transaction<'_, $db>, course_id: i32) -> Result<i64, MyError> {
+                let (enrolled,): (i64,) = sqlx::query_as(
+                    "SELECT COUNT(*) FROM enrollment
+                        WHERE course_id = ? AND status = ?"
+                )
+                    .bind(course_id)
+                    .bind(EnrollmentStatus::Enrolled.as_str())
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+                Ok(enrolled)
+            }
+        }
+    };
+}
+
+enrollment_repository!(mysql, MySql);
+enrollment_repository!(sqlite, Sqlite);
@@ -0,0 +1,30 @@
+mod audit;
+mod account;
+mod content;
+mod course;
+mod enrollment;
+mod review;
+mod student;
+mod teacher;
+mod trash;
+mod unit_of_work;
+
+use sqlx::{Database, QueryBuilder};
+
+/// MySQL 和 SQLite 的 SQL 方言差异。本目录的每个文件用宏为两种数据库的连接池各生成一份仓储实现，
+/// SQL 中与数据库有关的部分都从这里取得
+pub(crate) trait Dialect: Database {
+    /// 追加在 SELECT 之后锁定读取的行。SQLite 没有 FOR UPDATE，为空字符串，需要锁定时
+    /// 先执行一次不改变数据的 UPDATE（如 `SET version = version`）取得写锁，避免两个事务都读到旧值之后再争抢写锁
+    const FOR_UPDATE: &'static str;
+
+    /// 违反唯一约束时忽略这一行的 INSERT
+    const INSERT_IGNORE: &'static str;
+
+    /// INSERT 生成的自增主键
+    fn last_insert_id(result: &Self::QueryResult) -> i32;
+
+    /// 追加 ` JOIN (...) AS hits ON hits.id = course.id`：课程名称、描述与章节、课时标题任一命中即可，
+    /// 同一课程取最相关的一处，hits.score 越大越相关
+    fn push_search_hits(builder: &mut QueryBuilder<'_, Self>, q: &str);
+}
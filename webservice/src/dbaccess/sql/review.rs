@@ -0,0 +1,252 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, Sqlite, Transaction};
+use crate::dbaccess::db_now;
+use crate::dbaccess::review::{ReviewRepository, REVIEW_SELECT};
+use crate::dbaccess::unit_of_work::UnitOfWork;
+use crate::errors::MyError;
+use crate::models::enrollment::EnrollmentStatus;
+use crate::models::review::{CreateReview, RatingSummary, Review, ReviewQuery, UpdateReview};
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 ReviewRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! review_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+            use super::super::course::$module::ensure_course_exists;
+            use super::super::unit_of_work::$module::SqlUnitOfWork;
+
+            #[async_trait]
+            impl ReviewRepository for Pool<$db> {
+                async fn post_new_review_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    student_id: i32,
+                    new_review: CreateReview,
+                ) -> Result<Review, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    // 锁定课程行，同一课程的评价依次检查和写入，避免同一学生重复评价
+                    uow.lock_course_db(teacher_id, course_id).await?;
+
+                    let enrolled: Option<(i32,)> = sqlx::query_as(
+                        "SELECT id FROM enrollment
+                            WHERE course_id = ? AND student_id = ? AND status = ?"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .bind(EnrollmentStatus::Enrolled.as_str())
+                        .fetch_optional(&mut uow.tx)
+                        .await?;
+                    if enrolled.is_none() {
+                        return Err(MyError::Forbidden("Only enrolled students can review this course".into()));
+                    }
+
+                    let existing: Option<(i32,)> = sqlx::query_as(
+                        "SELECT id FROM review
+                            WHERE course_id = ? AND student_id = ?"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .fetch_optional(&mut uow.tx)
+                        .await?;
+                    if existing.is_some() {
+                        return Err(MyError::Conflict("Student has already reviewed this course".into()));
+                    }
+
+                    let now = db_now();
+                    let row = sqlx::query(
+                        "INSERT INTO review (course_id, student_id, rating, comment, created_at, updated_at)
+                            VALUES (?, ?, ?, ?, ?, ?)"
+                    )
+                        .bind(course_id)
+                        .bind(student_id)
+                        .bind(new_review.rating)
+                        .bind(new_review.comment)
+                        .bind(now)
+                        .bind(now)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let review = find_review(&mut uow.tx, teacher_id, course_id, <$db>::last_insert_id(&row)).await?;
+                    uow.record_audit_db(ctx, AuditRecord::created(AuditEntity::Review, review.id, &review)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(review)
+                }
+
+                async fn get_review_db(&self, teacher_id: i32, course_id: i32, review_id: i32) -> Result<Review, MyError> {
+                    find_review(self, teacher_id, course_id, review_id).await
+                }
+
+                async fn update_review_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    review_id: i32,
+                    update_review: UpdateReview,
+                ) -> Result<Review, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let current_review_row = lock_review(&mut uow.tx, teacher_id, course_id, review_id).await?;
+
+                    let row = sqlx::query(
+                        "UPDATE review
+                            SET rating = ?, comment = ?, updated_at = ?
+                            WHERE id = ?"
+                    )
+                        .bind(update_review.rating.unwrap_or(current_review_row.rating))
+                        .bind(update_review.comment.unwrap_or_else(|| current_review_row.comment.clone()))
+                        .bind(db_now())
+                        .bind(review_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("review", "Review Id not found"));
+                    }
+
+                    let review = find_review(&mut uow.tx, teacher_id, course_id, review_id).await?;
+                    uow.record_audit_db(
+                        ctx,
+                        AuditRecord::updated(AuditEntity::Review, review_id, &current_review_row, &review),
+                    )
+                    .await?;
+
+                    uow.commit().await?;
+
+                    Ok(review)
+                }
+
+                async fn delete_review_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    review_id: i32,
+                ) -> Result<(), MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let review = find_review(&mut uow.tx, teacher_id, course_id, review_id).await?;
+
+                    let row = sqlx::query(
+                        "DELETE FROM review
+                            WHERE id = ?"
+                    )
+                        .bind(review_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("review", "Review Id not found"));
+                    }
+
+                    uow.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Review, review_id, &review)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(())
+                }
+
+                async fn get_reviews_for_course_db(
+                    &self,
+                    teacher_id: i32,
+                    course_id: i32,
+                    query: &ReviewQuery,
+                ) -> Result<(Vec<Review>, i64), MyError> {
+                    ensure_course_exists(self, teacher_id, course_id).await?;
+
+                    let (total,): (i64,) = sqlx::query_as(
+                        "SELECT COUNT(*) FROM review
+                            WHERE course_id = ?"
+                    )
+                        .bind(course_id)
+                        .fetch_one(self)
+                        .await?;
+
+                    let rows: Vec<Review> = sqlx::query_as(&format!(
+                        "{}
+                            WHERE review.course_id = ?
+                            ORDER BY review.created_at DESC, review.id DESC
+                            LIMIT ? OFFSET ?",
+                        REVIEW_SELECT
+                    ))
+                        .bind(course_id)
+                        .bind(query.limit())
+                        .bind(query.offset())
+                        .fetch_all(self) // 获取当前页的记录
+                        .await?;
+
+                    Ok((rows, total))
+                }
+
+                async fn get_teacher_rating_db(&self, teacher_id: i32) -> Result<RatingSummary, MyError> {
+                    let rating = sqlx::query_as(
+                        "SELECT CAST(AVG(review.rating) AS DOUBLE) AS avg_rating, COUNT(review.id) AS review_count
+                            FROM review
+                            JOIN course ON course.id = review.course_id AND course.deleted_at IS NULL
+                            WHERE course.teacher_id = ?"
+                    )
+                        .bind(teacher_id)
+                        .fetch_one(self)
+                        .await?;
+
+                    Ok(rating)
+                }
+            }
+
+            async fn find_review<'e, E>(executor: E, teacher_id: i32, course_id: i32, review_id: i32) -> Result<Review, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query_as(&format!(
+                    "{}
+                        WHERE course.teacher_id = ? AND review.course_id = ? AND review.id = ?",
+                    REVIEW_SELECT
+                ))
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .bind(review_id)
+                    .fetch_optional(executor) // 获取单条记录
+                    .await?
+                    .ok_or_else(|| MyError::not_found("review", "Review Id not found"))
+            }
+
+            /// 锁定评价行后读取，修改基于的旧值在提交前不会被其他事务改变
+            async fn lock_review(
+                tx: &mut Transaction<'_, $db>,
+                teacher_id: i32,
+                course_id: i32,
+                review_id: i32,
+            ) -> Result<Review, MyError> {
+                if <$db>::FOR_UPDATE.is_empty() {
+                    sqlx::query("UPDATE review SET rating = rating WHERE id = ?")
+                        .bind(review_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                sqlx::query_as(&format!(
+                    "{}
+                        WHERE course.teacher_id = ? AND review.course_id = ? AND review.id = ?{}",
+                    REVIEW_SELECT,
+                    <$db>::FOR_UPDATE
+                ))
+                    .bind(teacher_id)
+                    .bind(course_id)
+                    .bind(review_id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .ok_or_else(|| MyError::not_found("review", "Review Id not found"))
+            }
+        }
+    };
+}
+
+review_repository!(mysql, MySql);
+review_repository!(sqlite, Sqlite);
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, Sqlite};
+use serde_json::json;
+use crate::dbaccess::student::StudentRepository;
+use crate::errors::MyError;
+use crate::models::auth::Role;
+use crate::models::student::{CreateStudent, Student};
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 StudentRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! student_repository {
+    ($module:ident, $db:ty) => {
+        mod $module {
+            use super::*;
+            use super::super::audit::$module::insert_audit;
+
+            #[async_trait]
+            impl StudentRepository for Pool<$db> {
+                async fn register_student_db(
+                    &self,
+                    ctx: &AuditContext,
+                    new_student: CreateStudent,
+                    username: &str,
+                    password_hash: &str,
+                ) -> Result<Student, MyError> {
+                    let mut tx = self.begin().await?;
+
+                    let account_row = sqlx::query(
+                        "INSERT INTO account (username, password_hash)
+                            VALUES (?, ?)"
+                    )
+                        .bind(username)
+                        .bind(password_hash)
+                        .execute(&mut tx)
+                        .await?;
+                    let account_id = <$db>::last_insert_id(&account_row);
+
+                    sqlx::query(
+                        "INSERT INTO account_role (account_id, role)
+                            VALUES (?, ?)"
+                    )
+                        .bind(account_id)
+                        .bind(Role::Student.as_str())
+                        .execute(&mut tx)
+                        .await?;
+
+                    let student_row = sqlx::query(
+                        "INSERT INTO student (account_id, name, email)
+                            VALUES (?, ?, ?)"
+                    )
+                        .bind(account_id)
+                        .bind(new_student.name)
+                        .bind(new_student.email)
+                        .execute(&mut tx)
+                        .await?;
+
+                    let student = find_student(&mut tx, <$db>::last_insert_id(&student_row)).await?;
+                    // 审计日志中不记录密码哈希
+                    let account = json!({ "username": username, "roles": [Role::Student] });
+                    insert_audit(&mut tx, ctx, AuditRecord::created(AuditEntity::Account, account_id, &account)).await?;
+                    insert_audit(&mut tx, ctx, AuditRecord::created(AuditEntity::Student, student.id, &student)).await?;
+
+                    tx.commit().await?;
+
+                    Ok(student)
+                }
+
+                async fn get_student_details_db(&self, student_id: i32) -> Result<Student, MyError> {
+                    find_student(self, student_id).await
+                }
+            }
+
+            async fn find_student<'e, E>(executor: E, student_id: i32) -> Result<Student, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                sqlx::query_as(
+                    "SELECT id, name, email
+                            FROM student
+                            WHERE id = ?"
+                )
+                    .bind(student_id)
+                    .fetch_optional(executor)
+                    .await?
+                    .ok_or_else(|| MyError::not_found("student", "Student Id not found"))
+            }
+        }
+    };
+}
+
+student_repository!(mysql, MySql);
+student_repository!(sqlite, Sqlite);
@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+use sqlx::{Executor, MySql, Pool, Sqlite};
+use crate::dbaccess::db_now;
+use crate::dbaccess::teacher::TeacherRepository;
+use crate::dbaccess::unit_of_work::UnitOfWorkRepository;
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use crate::models::teacher::{CreateTeacher, DeletePolicy, Teacher, UpdateTeacher};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 TeacherRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! teacher_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+            use super::super::audit::$module::insert_audit;
+
+            #[async_trait]
+            impl TeacherRepository for Pool<$db> {
+                async fn post_new_teacher_db(&self, ctx: &AuditContext, new_teacher: CreateTeacher) -> Result<Teacher, MyError> {
+                    let mut tx = self.begin().await?;
+
+                    let now = db_now();
+                    let row = sqlx::query(
+                        "INSERT INTO teacher (name, picture_url, profile, created_at, updated_at)
+                            VALUES (?, ?, ?, ?, ?)"
+                    )
+                        .bind(new_teacher.name)
+                        .bind(new_teacher.picture_url)
+                        .bind(new_teacher.profile)
+                        .bind(now)
+                        .bind(now)
+                        .execute(&mut tx)
+                        .await?;
+
+                    let teacher = find_teacher(&mut tx, <$db>::last_insert_id(&row)).await?;
+                    insert_audit(&mut tx, ctx, AuditRecord::created(AuditEntity::Teacher, teacher.id, &teacher)).await?;
+
+                    tx.commit().await?;
+
+                    Ok(teacher)
+                }
+
+                async fn delete_teacher_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    policy: DeletePolicy,
+                    expected_version: Option<i32>,
+                ) -> Result<(), MyError> {
+                    let mut uow = self.begin_db().await?;
+                    uow.delete_teacher_db(ctx, teacher_id, policy, expected_version).await?;
+                    uow.commit().await
+                }
+
+                async fn update_teacher_details_db(
+                    &self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    update_teacher: UpdateTeacher,
+                    expected_version: Option<i32>,
+                ) -> Result<Teacher, MyError> {
+                    let mut uow = self.begin_db().await?;
+                    let teacher = uow
+                        .update_teacher_details_db(ctx, teacher_id, update_teacher, expected_version)
+                        .await?;
+                    uow.commit().await?;
+
+                    Ok(teacher)
+                }
+
+                async fn get_all_teachers_db(&self) -> Result<Vec<Teacher>, MyError> {
+                    // 没有教师时返回空列表
+                    let rows: Vec<Teacher> = sqlx::query_as(
+                        "SELECT id, name, picture_url, profile, version, created_at, updated_at
+                                FROM teacher
+                                WHERE deleted_at IS NULL"
+                    )
+                        .fetch_all(self) // 获取所有记录
+                        .await?;
+
+                    Ok(rows)
+                }
+
+                async fn get_teacher_details_db(&self, teacher_id: i32) -> Result<Teacher, MyError> {
+                    find_teacher(self, teacher_id).await
+                }
+            }
+
+            /// 返回未删除的教师，不存在时返回 NotFound；连接池和事务共用
+            pub(crate) async fn find_teacher<'e, E>(executor: E, teacher_id: i32) -> Result<Teacher, MyError>
+            where
+                E: Executor<'e, Database = $db>,
+            {
+                let row: Option<Teacher> = sqlx::query_as(
+                    "SELECT id, name, picture_url, profile, version, created_at, updated_at
+                            FROM teacher
+                            WHERE id = ? AND deleted_at IS NULL"
+                )
+                    .bind(teacher_id)
+                    .fetch_optional(executor) // 获取单条记录，不存在时为 None
+                    .await?;
+
+                row.ok_or_else(|| MyError::not_found("teacher", "Teacher Id not found"))
+            }
+        }
+    };
+}
+
+teacher_repository!(mysql, MySql);
+teacher_repository!(sqlite, Sqlite);
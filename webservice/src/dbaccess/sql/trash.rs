@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{MySql, Pool, QueryBuilder, Sqlite};
+use crate::dbaccess::db_now;
+use crate::dbaccess::trash::TrashRepository;
+use crate::dbaccess::unit_of_work::UnitOfWork;
+use crate::errors::MyError;
+use crate::models::course::Course;
+use crate::models::teacher::Teacher;
+use crate::models::trash::{DeletedCourse, DeletedTeacher, PurgeSummary, Trash};
+use crate::models::audit::{AuditAction, AuditContext, AuditEntity, AuditRecord};
+
+/// 生成 MySQL 或 SQLite 的 TrashRepository 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! trash_repository {
+    ($module:ident, $db:ty) => {
+        mod $module {
+            use super::*;
+            use super::super::course::$module::find_course;
+            use super::super::teacher::$module::find_teacher;
+            use super::super::unit_of_work::$module::SqlUnitOfWork;
+
+            #[async_trait]
+            impl TrashRepository for Pool<$db> {
+                async fn get_trash_db(&self, teacher_id: Option<i32>) -> Result<Trash, MyError> {
+                    // 教师只能看到自己已删除的课程
+                    let teachers: Vec<DeletedTeacher> = match teacher_id {
+                        Some(_) => vec![],
+                        None => sqlx::query_as(
+                            "SELECT id, name, deleted_at FROM teacher
+                                WHERE deleted_at IS NOT NULL
+                                ORDER BY deleted_at DESC, id"
+                        )
+                            .fetch_all(self)
+                            .await?,
+                    };
+
+                    let mut courses_query = QueryBuilder::new(
+                        "SELECT teacher_id, id, name, deleted_at FROM course
+                            WHERE deleted_at IS NOT NULL"
+                    );
+                    if let Some(teacher_id) = teacher_id {
+                        courses_query.push(" AND teacher_id = ").push_bind(teacher_id);
+                    }
+                    courses_query.push(" ORDER BY deleted_at DESC, id");
+                    let courses: Vec<DeletedCourse> = courses_query
+                        .build_query_as()
+                        .fetch_all(self)
+                        .await?;
+
+                    Ok(Trash { teachers, courses })
+                }
+
+                async fn restore_course_db(&self, ctx: &AuditContext, teacher_id: i32, course_id: i32) -> Result<Course, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let row: Option<(Option<NaiveDateTime>,)> = sqlx::query_as(
+                        "SELECT teacher.deleted_at FROM course
+                            JOIN teacher ON teacher.id = course.teacher_id
+                            WHERE course.teacher_id = ? AND course.id = ? AND course.deleted_at IS NOT NULL"
+                    )
+                        .bind(teacher_id)
+                        .bind(course_id)
+                        .fetch_optional(&mut uow.tx)
+                        .await?;
+                    match row {
+                        None => return Err(MyError::not_found("course", "Course Id not found in trash")),
+                        Some((Some(_),)) => {
+                            return Err(MyError::Conflict("Restore the teacher before restoring this course".into()))
+                        }
+                        Some((None,)) => {}
+                    }
+
+                    let row = sqlx::query(
+                        "UPDATE course SET deleted_at = NULL, updated_at = ?, version = version + 1
+                            WHERE id = ? AND deleted_at IS NOT NULL"
+                    )
+                        .bind(db_now())
+                        .bind(course_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    if row.rows_affected() == 0 {
+                        return Err(MyError::not_found("course", "Course Id not found in trash"));
+                    }
+
+                    let course = find_course(&mut uow.tx, teacher_id, course_id).await?;
+                    uow.record_audit_db(ctx, restored(AuditEntity::Course, course_id, &course)).await?;
+
+                    uow.commit().await?;
+
+                    Ok(course)
+                }
+
+                async fn restore_teacher_db(&self, ctx: &AuditContext, teacher_id: i32) -> Result<Teacher, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let (deleted_at,): (NaiveDateTime,) = sqlx::query_as(
+                        "SELECT deleted_at FROM teacher
+                            WHERE id = ? AND deleted_at IS NOT NULL"
+                    )
+                        .bind(teacher_id)
+                        .fetch_optional(&mut uow.tx)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("teacher", "Teacher Id not found in trash"))?;
+
+                    // 级联删除的课程与教师的 deleted_at 相同，之前单独删除的课程仍留在回收站
+                    let course_ids: Vec<(i32,)> = sqlx::query_as(
+                        "SELECT id FROM course
+                            WHERE teacher_id = ? AND deleted_at = ?"
+                    )
+                        .bind(teacher_id)
+                        .bind(deleted_at)
+                        .fetch_all(&mut uow.tx)
+                        .await?;
+
+                    let now = db_now();
+                    sqlx::query(
+                        "UPDATE course SET deleted_at = NULL, updated_at = ?, version = version + 1
+                            WHERE teacher_id = ? AND deleted_at = ?"
+                    )
+                        .bind(now)
+                        .bind(teacher_id)
+                        .bind(deleted_at)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    sqlx::query(
+                        "UPDATE teacher SET deleted_at = NULL, updated_at = ?, version = version + 1
+                            WHERE id = ?"
+                    )
+                        .bind(now)
+                        .bind(teacher_id)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let teacher = find_teacher(&mut uow.tx, teacher_id).await?;
+                    uow.record_audit_db(ctx, restored(AuditEntity::Teacher, teacher_id, &teacher)).await?;
+                    for (course_id,) in course_ids {
+                        let course = find_course(&mut uow.tx, teacher_id, course_id).await?;
+                        uow.record_audit_db(ctx, restored(AuditEntity::Course, course_id, &course)).await?;
+                    }
+
+                    uow.commit().await?;
+
+                    Ok(teacher)
+                }
+
+                async fn purge_deleted_db(&self, ctx: &AuditContext, before: NaiveDateTime) -> Result<PurgeSummary, MyError> {
+                    let mut uow = SqlUnitOfWork::begin(self).await?;
+
+                    let purged_courses: Vec<DeletedCourse> = sqlx::query_as(
+                        "SELECT teacher_id, id, name, deleted_at FROM course
+                            WHERE deleted_at < ?"
+                    )
+                        .bind(before)
+                        .fetch_all(&mut uow.tx)
+                        .await?;
+
+                    // 选课、评价、章节和课时通过外键 ON DELETE CASCADE 一并删除
+                    let courses = sqlx::query(
+                        "DELETE FROM course
+                            WHERE deleted_at < ?"
+                    )
+                        .bind(before)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    let purged_teachers: Vec<DeletedTeacher> = sqlx::query_as(
+                        "SELECT id, name, deleted_at FROM teacher
+                            WHERE deleted_at < ?
+                            AND NOT EXISTS (SELECT 1 FROM course WHERE course.teacher_id = teacher.id)"
+                    )
+                        .bind(before)
+                        .fetch_all(&mut uow.tx)
+                        .await?;
+
+                    let teachers = sqlx::query(
+                        "DELETE FROM teacher
+                            WHERE deleted_at < ?
+                            AND NOT EXISTS (SELECT 1 FROM course WHERE course.teacher_id = teacher.id)"
+                    )
+                        .bind(before)
+                        .execute(&mut uow.tx)
+                        .await?;
+
+                    for course in &purged_courses {
+                        let record = AuditRecord::deleted(AuditEntity::Course, course.id, course).with_action(AuditAction::Purge);
+                        uow.record_audit_db(ctx, record).await?;
+                    }
+                    for teacher in &purged_teachers {
+                        let record = AuditRecord::deleted(AuditEntity::Teacher, teacher.id, teacher).with_action(AuditAction::Purge);
+                        uow.record_audit_db(ctx, record).await?;
+                    }
+
+                    uow.commit().await?;
+
+                    Ok(PurgeSummary {
+                        teachers: teachers.rows_affected(),
+                        courses: courses.rows_affected(),
+                    })
+                }
+            }
+
+            /// 恢复后的完整记录，以 Restore 记入审计日志
+            fn restored(entity: AuditEntity, entity_id: i32, after: &impl serde::Serialize) -> AuditRecord {
+                AuditRecord::created(entity, entity_id, after).with_action(AuditAction::Restore)
+            }
+        }
+    };
+}
+
+trash_repository!(mysql, MySql);
+trash_repository!(sqlite, Sqlite);
@@ -0,0 +1,360 @@
+use async_trait::async_trait;
+use serde_json::json;
+use sqlx::{MySql, Pool, QueryBuilder, Sqlite, Transaction};
+use super::course::COURSE_COLUMNS;
+use crate::dbaccess::course::push_course_changes;
+use crate::dbaccess::unit_of_work::{UnitOfWork, UnitOfWorkRepository};
+use crate::dbaccess::{db_now, version_mismatch};
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditEntity, AuditRecord};
+use crate::models::course::{Course, CreateCourse, UpdateCourse};
+use crate::models::teacher::{DeletePolicy, Teacher, UpdateTeacher};
+use super::Dialect;
+
+/// 生成 MySQL 或 SQLite 的 UnitOfWorkRepository 和 UnitOfWork 实现，$module 为生成的模块名，$db 为数据库类型
+macro_rules! unit_of_work_repository {
+    ($module:ident, $db:ty) => {
+        pub(super) mod $module {
+            use super::*;
+            use super::super::audit::$module::insert_audit;
+            use super::super::enrollment::$module::promote_waitlist;
+
+            /// 仓储中一次修改多行的方法也通过它开启事务，共用同一套加锁和审计逻辑
+            pub(crate) struct SqlUnitOfWork {
+                pub(crate) tx: Transaction<'static, $db>,
+            }
+
+            #[async_trait]
+            impl UnitOfWorkRepository for Pool<$db> {
+                async fn begin_db(&self) -> Result<Box<dyn UnitOfWork>, MyError> {
+                    Ok(SqlUnitOfWork::begin(self).await?)
+                }
+            }
+
+            impl SqlUnitOfWork {
+                pub(crate) async fn begin(pool: &Pool<$db>) -> Result<Box<Self>, MyError> {
+                    Ok(Box::new(SqlUnitOfWork { tx: pool.begin().await? }))
+                }
+
+                /// 返回未删除的课程，不存在时为 None；MySQL 同时锁定课程行，不锁子查询读取的课时和评价
+                async fn find_course(&mut self, teacher_id: i32, course_id: i32) -> Result<Option<Course>, MyError> {
+                    let course = sqlx::query_as(&format!(
+                        "SELECT {} FROM course
+                            WHERE teacher_id = ? AND id = ? AND deleted_at IS NULL{}",
+                        COURSE_COLUMNS,
+                        <$db>::FOR_UPDATE
+                    ))
+                        .bind(teacher_id)
+                        .bind(course_id)
+                        .fetch_optional(&mut self.tx)
+                        .await?;
+
+                    Ok(course)
+                }
+
+                /// 锁定并返回未删除的教师，不存在时为 None
+                async fn find_teacher(&mut self, teacher_id: i32) -> Result<Option<Teacher>, MyError> {
+                    if <$db>::FOR_UPDATE.is_empty() {
+                        sqlx::query("UPDATE teacher SET version = version WHERE id = ?")
+                            .bind(teacher_id)
+                            .execute(&mut self.tx)
+                            .await?;
+                    }
+
+                    let teacher = sqlx::query_as(&format!(
+                        "SELECT id, name, picture_url, profile, version, created_at, updated_at FROM teacher
+                            WHERE id = ? AND deleted_at IS NULL{}",
+                        <$db>::FOR_UPDATE
+                    ))
+                        .bind(teacher_id)
+                        .fetch_optional(&mut self.tx)
+                        .await?;
+
+                    Ok(teacher)
+                }
+            }
+
+            #[async_trait]
+            impl UnitOfWork for SqlUnitOfWork {
+                async fn lock_course_db(&mut self, teacher_id: i32, course_id: i32) -> Result<Course, MyError> {
+                    if <$db>::FOR_UPDATE.is_empty() {
+                        sqlx::query("UPDATE course SET version = version WHERE teacher_id = ? AND id = ?")
+                            .bind(teacher_id)
+                            .bind(course_id)
+                            .execute(&mut self.tx)
+                            .await?;
+                    }
+
+                    self.find_course(teacher_id, course_id)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("course", "Course Id not found"))
+                }
+
+                async fn lock_teacher_db(&mut self, teacher_id: i32) -> Result<Teacher, MyError> {
+                    self.find_teacher(teacher_id)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("teacher", "Teacher Id not found"))
+                }
+
+                async fn post_new_course_db(&mut self, ctx: &AuditContext, new_course: CreateCourse) -> Result<Course, MyError> {
+                    let teacher_id = new_course.teacher_id;
+                    let now = db_now();
+                    let row = sqlx::query(
+                        "INSERT INTO course (teacher_id, name, time, description, format, price, language, level, capacity, created_at, updated_at)
+                            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    )
+                        .bind(new_course.teacher_id)
+                        .bind(new_course.name)
+                        .bind(new_course.time)
+                        .bind(new_course.description)
+                        .bind(new_course.format)
+                        .bind(new_course.price)
+                        .bind(new_course.language)
+                        .bind(new_course.level)
+                        .bind(new_course.capacity)
+                        .bind(now)
+                        .bind(now)
+                        .execute(&mut self.tx)
+                        .await?;
+
+                    let course = self
+                        .find_course(teacher_id, <$db>::last_insert_id(&row))
+                        .await?
+                        .ok_or_else(|| MyError::not_found("course", "Course Id not found"))?;
+                    self.record_audit_db(ctx, AuditRecord::created(AuditEntity::Course, course.id, &course)).await?;
+
+                    Ok(course)
+                }
+
+                async fn update_course_details_db(
+                    &mut self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    update_course: UpdateCourse,
+                    expected_version: Option<i32>,
+                ) -> Result<Course, MyError> {
+                    let current_course_row = self.lock_course_db(teacher_id, course_id).await?;
+                    if expected_version.is_some_and(|version| version != current_course_row.version) {
+                        return Err(version_mismatch("Course"));
+                    }
+
+                    // 只更新请求中提供的字段，其余字段保持原值
+                    let mut update_query = QueryBuilder::new("UPDATE course SET ");
+                    if !push_course_changes(&mut update_query, &update_course) {
+                        return Ok(current_course_row);
+                    }
+                    update_query
+                        .push(", updated_at = ")
+                        .push_bind(db_now())
+                        .push(", version = version + 1 WHERE id = ")
+                        .push_bind(course_id);
+                    update_query
+                        .build()
+                        .execute(&mut self.tx)
+                        .await?;
+
+                    let course = self
+                        .find_course(teacher_id, course_id)
+                        .await?
+                        .ok_or_else(|| MyError::not_found("course", "Course Id not found"))?;
+                    self.record_audit_db(
+                        ctx,
+                        AuditRecord::updated(AuditEntity::Course, course_id, &current_course_row, &course),
+                    )
+                    .await?;
+
+                    // 容量调大或取消限制后，多出的名额按候补顺序递补
+                    if update_course.capacity.is_some() {
+                        for record in promote_waitlist(&mut self.tx, course_id, course.capacity).await? {
+                            self.record_audit_db(ctx, record).await?;
+                        }
+                    }
+
+                    Ok(course)
+                }
+
+                async fn delete_course_db(
+                    &mut self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    course_id: i32,
+                    expected_version: Option<i32>,
+                ) -> Result<(), MyError> {
+                    let course = self.lock_course_db(teacher_id, course_id).await?;
+                    if expected_version.is_some_and(|version| version != course.version) {
+                        return Err(version_mismatch("Course"));
+                    }
+
+                    let now = db_now();
+                    sqlx::query(
+                        "UPDATE course SET deleted_at = ?, updated_at = ?, version = version + 1
+                            WHERE id = ?"
+                    )
+                        .bind(now)
+                        .bind(now)
+                        .bind(course_id)
+                        .execute(&mut self.tx)
+                        .await?;
+
+                    self.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Course, course_id, &course)).await
+                }
+
+                async fn update_teacher_details_db(
+                    &mut self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    update_teacher: UpdateTeacher,
+                    expected_version: Option<i32>,
+                ) -> Result<Teacher, MyError> {
+                    let current_teacher_row = self.lock_teacher_db(teacher_id).await?;
+                    if expected_version.is_some_and(|version| version != current_teacher_row.version) {
+                        return Err(version_mismatch("Teacher"));
+                    }
+
+                    let teacher = Teacher {
+                        id: current_teacher_row.id,
+                        name: if let Some(name) = update_teacher.name {
+                            name
+                        } else {
+                            current_teacher_row.name.clone()
+                        },
+                        picture_url: if let Some(picture_url) = update_teacher.picture_url {
+                            picture_url
+                        } else {
+                            current_teacher_row.picture_url.clone()
+                        },
+                        profile: if let Some(profile) = update_teacher.profile {
+                            profile
+                        } else {
+                            current_teacher_row.profile.clone()
+                        },
+                        version: current_teacher_row.version + 1,
+                        created_at: current_teacher_row.created_at,
+                        updated_at: db_now(),
+                    };
+
+                    sqlx::query(
+                        "UPDATE teacher
+                            SET name = ?, picture_url = ?, profile = ?, updated_at = ?, version = version + 1
+                            WHERE id = ?"
+                    )
+                        .bind(&teacher.name)
+                        .bind(&teacher.picture_url)
+                        .bind(&teacher.profile)
+                        .bind(teacher.updated_at)
+                        .bind(teacher_id)
+                        .execute(&mut self.tx)
+                        .await?;
+
+                    self.record_audit_db(
+                        ctx,
+                        AuditRecord::updated(AuditEntity::Teacher, teacher_id, &current_teacher_row, &teacher),
+                    )
+                    .await?;
+
+                    Ok(teacher)
+                }
+
+                async fn delete_teacher_db(
+                    &mut self,
+                    ctx: &AuditContext,
+                    teacher_id: i32,
+                    policy: DeletePolicy,
+                    expected_version: Option<i32>,
+                ) -> Result<(), MyError> {
+                    let now = db_now();
+
+                    let teacher = self.lock_teacher_db(teacher_id).await?;
+                    if expected_version.is_some_and(|expected| expected != teacher.version) {
+                        return Err(version_mismatch("Teacher"));
+                    }
+
+                    let courses: Vec<Course> = sqlx::query_as(&format!(
+                        "SELECT {} FROM course
+                            WHERE teacher_id = ? AND deleted_at IS NULL",
+                        COURSE_COLUMNS
+                    ))
+                        .bind(teacher_id)
+                        .fetch_all(&mut self.tx)
+                        .await?;
+
+                    match policy {
+                        DeletePolicy::Reject => {
+                            if !courses.is_empty() {
+                                return Err(MyError::Conflict(format!(
+                                    "Teacher still has {} course(s); use on_courses=cascade or on_courses=reassign",
+                                    courses.len()
+                                )));
+                            }
+                        }
+                        DeletePolicy::Cascade => {
+                            // 课程与教师使用相同的 deleted_at，恢复教师时一并恢复
+                            sqlx::query(
+                                "UPDATE course SET deleted_at = ?, updated_at = ?, version = version + 1
+                                    WHERE teacher_id = ? AND deleted_at IS NULL"
+                            )
+                                .bind(now)
+                                .bind(now)
+                                .bind(teacher_id)
+                                .execute(&mut self.tx)
+                                .await?;
+                            for course in &courses {
+                                self.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Course, course.id, course))
+                                    .await?;
+                            }
+                        }
+                        DeletePolicy::Reassign(new_teacher_id) => {
+                            if self.find_teacher(new_teacher_id).await?.is_none() {
+                                return Err(MyError::invalid_field("reassign_to", "Teacher does not exist"));
+                            }
+                            sqlx::query(
+                                "UPDATE course SET teacher_id = ?, updated_at = ?, version = version + 1
+                                    WHERE teacher_id = ? AND deleted_at IS NULL"
+                            )
+                                .bind(new_teacher_id)
+                                .bind(now)
+                                .bind(teacher_id)
+                                .execute(&mut self.tx)
+                                .await?;
+                            for course in &courses {
+                                let record = AuditRecord::updated(
+                                    AuditEntity::Course,
+                                    course.id,
+                                    &json!({ "teacher_id": teacher_id }),
+                                    &json!({ "teacher_id": new_teacher_id }),
+                                );
+                                self.record_audit_db(ctx, record).await?;
+                            }
+                        }
+                    }
+
+                    sqlx::query(
+                        "UPDATE teacher SET deleted_at = ?, updated_at = ?, version = version + 1
+                            WHERE id = ?"
+                    )
+                        .bind(now)
+                        .bind(now)
+                        .bind(teacher_id)
+                        .execute(&mut self.tx)
+                        .await?;
+
+                    self.record_audit_db(ctx, AuditRecord::deleted(AuditEntity::Teacher, teacher_id, &teacher)).await
+                }
+
+                async fn record_audit_db(&mut self, ctx: &AuditContext, record: AuditRecord) -> Result<(), MyError> {
+                    insert_audit(&mut self.tx, ctx, record).await
+                }
+
+                async fn commit(self: Box<Self>) -> Result<(), MyError> {
+                    self.tx.commit().await?;
+
+                    Ok(())
+                }
+            }
+        }
+    };
+}
+
+unit_of_work_repository!(mysql, MySql);
+unit_of_work_repository!(sqlite, Sqlite);
@@ -0,0 +1,33 @@
+use sqlx::sqlite::SqliteQueryResult;
+use sqlx::{QueryBuilder, Sqlite};
+use super::sql::Dialect;
+
+impl Dialect for Sqlite {
+    const FOR_UPDATE: &'static str = "";
+
+    const INSERT_IGNORE: &'static str = "INSERT OR IGNORE";
+
+    fn last_insert_id(result: &SqliteQueryResult) -> i32 {
+        result.last_insert_rowid() as i32
+    }
+
+    fn push_search_hits(builder: &mut QueryBuilder<'_, Sqlite>, q: &str) {
+        // bm25 的值越小越相关，取负数后与 MySQL 的相关度方向一致
+        builder
+            .push(" JOIN (SELECT id, MAX(score) AS score FROM (")
+            .push("SELECT rowid AS id, -bm25(course_fts) AS score FROM course_fts WHERE course_fts MATCH ")
+            .push_bind(fts_query(q))
+            .push(" UNION ALL SELECT course_id AS id, -bm25(course_content_fts) AS score")
+            .push(" FROM course_content_fts WHERE course_content_fts MATCH ")
+            .push_bind(fts_query(q))
+            .push(") GROUP BY id) AS hits ON hits.id = course.id");
+    }
+}
+
+/// 把用户输入的关键字转换成 FTS5 查询，每个词加引号避免被当作 FTS5 语法，任一词命中即可
+fn fts_query(q: &str) -> String {
+    q.split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" OR ")
+}
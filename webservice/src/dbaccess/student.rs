@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use crate::errors::MyError;
+use crate::models::student::{CreateStudent, Student};
+use crate::models::audit::AuditContext;
+
+#[async_trait]
+pub trait StudentRepository {
+    /// 在同一个事务中创建登录账号和学生资料，账号获得 student 角色
+    async fn register_student_db(
+        &self,
+        ctx: &AuditContext,
+        new_student: CreateStudent,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<Student, MyError>;
+
+    /// 没有匹配的学生时返回 NotFound
+    async fn get_student_details_db(&self, student_id: i32) -> Result<Student, MyError>;
+}
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use crate::errors::MyError;
+use crate::models::course::Course;
+use crate::models::teacher::Teacher;
+use crate::models::trash::{PurgeSummary, Trash};
+use crate::models::audit::AuditContext;
+
+#[async_trait]
+pub trait TrashRepository {
+    /// teacher_id 为 None 时返回全部已删除的教师和课程，否则只返回该教师已删除的课程
+    async fn get_trash_db(&self, teacher_id: Option<i32>) -> Result<Trash, MyError>;
+
+    /// 恢复回收站中的课程；所属教师仍在回收站中时返回 Conflict
+    async fn restore_course_db(&self, ctx: &AuditContext, teacher_id: i32, course_id: i32) -> Result<Course, MyError>;
+
+    /// 恢复回收站中的教师，以及与其一起删除的课程
+    async fn restore_teacher_db(&self, ctx: &AuditContext, teacher_id: i32) -> Result<Teacher, MyError>;
+
+    /// 物理删除 deleted_at 早于 before 的课程和教师，仍有课程的教师留到下次清理
+    async fn purge_deleted_db(&self, ctx: &AuditContext, before: NaiveDateTime) -> Result<PurgeSummary, MyError>;
+}
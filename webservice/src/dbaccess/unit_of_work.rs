@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use crate::errors::MyError;
+use crate::models::audit::{AuditContext, AuditRecord};
+use crate::models::course::{Course, CreateCourse, UpdateCourse};
+use crate::models::teacher::{DeletePolicy, Teacher, UpdateTeacher};
+
+/// 在同一个事务中完成的一组修改：读取的行一直锁到事务结束，审计日志与修改一起提交
+///
+/// 没有调用 commit 就丢弃时回滚全部修改，出错后直接用 `?` 返回即可
+#[async_trait]
+pub trait UnitOfWork: Send {
+    /// 锁定并返回未删除的课程，不存在时返回 NotFound；同一课程上先检查后写入的操作
+    /// （选课、评价、新增和排序章节等）都先调用它，借此在事务中串行执行
+    async fn lock_course_db(&mut self, teacher_id: i32, course_id: i32) -> Result<Course, MyError>;
+
+    /// 锁定并返回未删除的教师，不存在时返回 NotFound
+    async fn lock_teacher_db(&mut self, teacher_id: i32) -> Result<Teacher, MyError>;
+
+    async fn post_new_course_db(&mut self, ctx: &AuditContext, new_course: CreateCourse) -> Result<Course, MyError>;
+
+    /// 含义同 CourseRepository::update_course_details_db
+    async fn update_course_details_db(
+        &mut self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        update_course: UpdateCourse,
+        expected_version: Option<i32>,
+    ) -> Result<Course, MyError>;
+
+    /// 含义同 CourseRepository::delete_course_db
+    async fn delete_course_db(
+        &mut self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        course_id: i32,
+        expected_version: Option<i32>,
+    ) -> Result<(), MyError>;
+
+    /// 含义同 TeacherRepository::update_teacher_details_db
+    async fn update_teacher_details_db(
+        &mut self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        update_teacher: UpdateTeacher,
+        expected_version: Option<i32>,
+    ) -> Result<Teacher, MyError>;
+
+    /// 含义同 TeacherRepository::delete_teacher_db
+    async fn delete_teacher_db(
+        &mut self,
+        ctx: &AuditContext,
+        teacher_id: i32,
+        policy: DeletePolicy,
+        expected_version: Option<i32>,
+    ) -> Result<(), MyError>;
+
+    async fn record_audit_db(&mut self, ctx: &AuditContext, record: AuditRecord) -> Result<(), MyError>;
+
+    async fn commit(self: Box<Self>) -> Result<(), MyError>;
+}
+
+#[async_trait]
+pub trait UnitOfWorkRepository {
+    /// 开始一个事务，处理器需要一次修改多个实体时使用
+    async fn begin_db(&self) -> Result<Box<dyn UnitOfWork>, MyError>;
+}
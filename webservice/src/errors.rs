@@ -1,23 +1,115 @@
-use actix_web::{error, http::StatusCode, HttpResponse, Result};
-use serde::Serialize;
-use sqlx::error::Error as SQLxError;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::JsonPayloadError;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{error, http::StatusCode, HttpMessage, HttpRequest, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::error::{DatabaseError, Error as SQLxError};
+use sqlx::mysql::MySqlDatabaseError;
 use std::fmt;
+use uuid::Uuid;
+
+/// 请求 ID 所在的请求头和响应头
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
 
 #[derive(Debug, Serialize)]
 pub enum MyError {
     DBError(String),
     ActixError(String),
-    #[allow(dead_code)]
-    NotFound(String),
+    /// 资源不存在，entity 为错误码的前缀，如 "course" 对应 `course.not_found`
+    NotFound { entity: &'static str, msg: String },
     InvalidInput(String),
+    Unauthorized(String),
+    Forbidden(String),
+    /// 请求中一个或多个字段没有通过校验
+    Validation(Vec<FieldError>),
+    /// 违反唯一约束，例如重复的用户名
+    UniqueViolation(String),
+    /// 违反外键约束，例如引用了不存在的教师
+    ForeignKeyViolation(String),
+    /// 违反 CHECK 约束
+    CheckViolation(String),
+    /// 请求与资源当前状态冲突，例如删除仍有课程的教师
+    Conflict(String),
+    /// If-Match 与资源当前的 ETag 不一致
+    PreconditionFailed(String),
 }
 
-#[derive(Debug, Serialize)]
+/// 单个字段的校验错误
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MyErrorResponse {
-    error_message: String,
+    /// 稳定的错误码，例如 `course.not_found`、`validation.failed`，客户端据此分支处理
+    pub code: String,
+    pub error_message: String,
+    /// 各字段的错误详情，只有校验错误才有
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    /// 与响应头 X-Request-Id 相同，便于对照服务端日志
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl MyError {
+    /// 只有一个字段出错的 Validation 错误
+    pub fn invalid_field(field: &str, message: &str) -> Self {
+        MyError::Validation(vec![FieldError {
+            field: field.into(),
+            message: message.into(),
+        }])
+    }
+
+    /// 资源不存在的错误，例如 `MyError::not_found("course", "Course Id not found")`
+    pub fn not_found(entity: &'static str, msg: &str) -> Self {
+        MyError::NotFound {
+            entity,
+            msg: msg.into(),
+        }
+    }
+
+    /// 错误码
+    pub fn code(&self) -> String {
+        match self {
+            MyError::DBError(_msg) => "db.error".into(),
+            MyError::ActixError(_msg) => "internal.error".into(),
+            MyError::NotFound { entity, .. } => format!("{}.not_found", entity),
+            MyError::InvalidInput(_msg) => "request.invalid".into(),
+            MyError::Unauthorized(_msg) => "auth.unauthorized".into(),
+            MyError::Forbidden(_msg) => "auth.forbidden".into(),
+            MyError::Validation(_errors) => "validation.failed".into(),
+            MyError::UniqueViolation(_msg) => "db.unique_violation".into(),
+            MyError::ForeignKeyViolation(_msg) => "db.foreign_key_violation".into(),
+            MyError::CheckViolation(_msg) => "db.check_violation".into(),
+            MyError::Conflict(_msg) => "resource.conflict".into(),
+            MyError::PreconditionFailed(_msg) => "precondition.failed".into(),
+        }
+    }
+
+    /// 错误响应体，批量操作中每个失败的操作也使用它
+    pub fn to_body(&self, request_id: Option<&str>) -> MyErrorResponse {
+        let details = match self {
+            MyError::Validation(errors) => errors.clone(),
+            _ => vec![],
+        };
+        MyErrorResponse {
+            code: self.code(),
+            error_message: self.error_response(),
+            details,
+            request_id: request_id.map(String::from),
+        }
+    }
+
+    /// 生成错误响应，request_id 由 request_id 中间件补上
+    fn to_response(&self, request_id: Option<&str>) -> HttpResponse {
+        HttpResponse::build(error::ResponseError::status_code(self)).json(self.to_body(request_id))
+    }
+
     fn error_response(&self) -> String {
         match self {
             MyError::DBError(msg) => {
@@ -28,7 +120,7 @@ impl MyError {
                 println!("Server error occurred: {:?}", msg);
                 "Internal server error".into()
             }
-            MyError::NotFound(msg) => {
+            MyError::NotFound { msg, .. } => {
                 println!("Not found error occurred: {:?}", msg);
                 msg.into()
             }
@@ -36,6 +128,38 @@ impl MyError {
                 println!("Invalid input error occurred: {:?}", msg);
                 msg.into()
             }
+            MyError::Unauthorized(msg) => {
+                println!("Unauthorized error occurred: {:?}", msg);
+                msg.into()
+            }
+            MyError::Forbidden(msg) => {
+                println!("Forbidden error occurred: {:?}", msg);
+                msg.into()
+            }
+            MyError::Validation(errors) => {
+                println!("Validation error occurred: {:?}", errors);
+                "Validation failed".into()
+            }
+            MyError::UniqueViolation(msg) => {
+                println!("Unique constraint violated: {:?}", msg);
+                "Record already exists".into()
+            }
+            MyError::ForeignKeyViolation(msg) => {
+                println!("Foreign key constraint violated: {:?}", msg);
+                "Referenced record does not exist or is still in use".into()
+            }
+            MyError::CheckViolation(msg) => {
+                println!("Check constraint violated: {:?}", msg);
+                "Value is not allowed".into()
+            }
+            MyError::Conflict(msg) => {
+                println!("Conflict error occurred: {:?}", msg);
+                msg.into()
+            }
+            MyError::PreconditionFailed(msg) => {
+                println!("Precondition failed: {:?}", msg);
+                msg.into()
+            }
         }
     }
 }
@@ -44,21 +168,27 @@ impl error::ResponseError for MyError {
     fn status_code(&self) -> StatusCode {
         match self {
             MyError::DBError(_msg) | MyError::ActixError(_msg) => StatusCode::INTERNAL_SERVER_ERROR,
-            MyError::NotFound(_msg) => StatusCode::NOT_FOUND,
+            MyError::NotFound { .. } => StatusCode::NOT_FOUND,
             MyError::InvalidInput(_msg) => StatusCode::BAD_REQUEST,
+            MyError::Unauthorized(_msg) => StatusCode::UNAUTHORIZED,
+            MyError::Forbidden(_msg) => StatusCode::FORBIDDEN,
+            MyError::Validation(_errors) => StatusCode::BAD_REQUEST,
+            MyError::UniqueViolation(_msg) | MyError::Conflict(_msg) => StatusCode::CONFLICT,
+            MyError::PreconditionFailed(_msg) => StatusCode::PRECONDITION_FAILED,
+            MyError::ForeignKeyViolation(_msg) | MyError::CheckViolation(_msg) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).json(MyErrorResponse {
-            error_message: self.error_response(),
-        })
+        self.to_response(None)
     }
 }
 
 impl fmt::Display for MyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f, "{}", self)
+        write!(f, "{:?}", self)
     }
 }
 
@@ -68,8 +198,97 @@ impl From<actix_web::error::Error> for MyError {
     }
 }
 
+/// 按数据库返回的错误号区分约束类型：MySQL 用 number()，SQLite 用扩展错误码
+fn constraint_violation(err: &dyn DatabaseError) -> Option<MyError> {
+    let msg = err.message().to_string();
+    let (unique, foreign_key, check) = match err.try_downcast_ref::<MySqlDatabaseError>() {
+        Some(err) => {
+            let number = err.number();
+            (number == 1062, matches!(number, 1216 | 1217 | 1451 | 1452), number == 3819)
+        }
+        None => {
+            let code = err.code().unwrap_or_default();
+            (matches!(code.as_ref(), "1555" | "2067"), code == "787", code == "275")
+        }
+    };
+    if unique {
+        Some(MyError::UniqueViolation(msg))
+    } else if foreign_key {
+        Some(MyError::ForeignKeyViolation(msg))
+    } else if check {
+        Some(MyError::CheckViolation(msg))
+    } else {
+        None
+    }
+}
+
 impl From<SQLxError> for MyError {
     fn from(err: SQLxError) -> Self {
-        MyError::DBError(err.to_string())
+        match &err {
+            SQLxError::RowNotFound => MyError::not_found("resource", "Record not found"),
+            SQLxError::Database(db_err) => constraint_violation(db_err.as_ref())
+                .unwrap_or_else(|| MyError::DBError(err.to_string())),
+            _ => MyError::DBError(err.to_string()),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// JsonConfig 的错误处理：字段缺失或类型不符时指出出错的字段，JSON 本身无法解析时返回通用错误
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> error::Error {
+    match err {
+        JsonPayloadError::Deserialize(err) if err.is_data() => {
+            let position = format!(" at line {} column {}", err.line(), err.column());
+            let message = err.to_string();
+            let message = message.strip_suffix(&position).unwrap_or(&message);
+            // serde 只在字段缺失时给出字段名，其余情况归到整个请求体
+            let field = message
+                .strip_prefix("missing field `")
+                .and_then(|rest| rest.strip_suffix('`'))
+                .unwrap_or("body");
+            MyError::Validation(vec![FieldError {
+                field: field.into(),
+                message: message.into(),
+            }])
+            .into()
+        }
+        _ => MyError::InvalidInput("Please provide valid json input".to_string()).into(),
+    }
+}
+
+/// 当前请求的 ID，由 request_id 中间件写入请求扩展，审计日志据此关联请求
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// 沿用客户端传入的 X-Request-Id（不超过 64 个字母、数字或 `-`），否则生成新的 UUID
+fn incoming_request_id(req: &ServiceRequest) -> Option<String> {
+    let id = req.headers().get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    valid.then(|| id.to_string())
+}
+
+/// 为每个请求分配请求 ID：写入响应头，并补到 MyError 生成的错误响应体中
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, error::Error> {
+    let id = incoming_request_id(&req).unwrap_or_else(|| Uuid::new_v4().to_string());
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let res = next.call(req).await?;
+    let body = res
+        .response()
+        .error()
+        .and_then(|err| err.as_error::<MyError>())
+        .map(|err| err.to_response(Some(&id)).into_body());
+    // 只替换响应体，保留内层中间件（如 CORS）已经写入的响应头
+    let mut res = match body {
+        Some(body) => res.map_body(|_, _| body),
+        None => res.map_into_boxed_body(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(res)
+}
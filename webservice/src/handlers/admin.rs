@@ -0,0 +1,164 @@
+use actix_web::{web, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::models::auth::{AccountRoles, Role};
+use crate::state::AppState;
+
+pub async fn get_account_roles(
+    app_state: web::Data<AppState>,
+    params: web::Path<i32>,
+) -> Result<HttpResponse, MyError> {
+    let account_id = params.into_inner();
+    app_state.db.get_account_db(account_id).await?;
+    app_state.db.get_account_roles_db(account_id)
+        .await
+        .map(|roles| HttpResponse::Ok().json(AccountRoles { account_id, roles }))
+}
+
+pub async fn grant_role(
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, String)>,
+) -> Result<HttpResponse, MyError> {
+    let (account_id, role) = params.into_inner();
+    let role: Role = role.parse()?;
+    app_state.db.grant_role_db(&audit, account_id, role)
+        .await
+        .map(|roles| HttpResponse::Ok().json(AccountRoles { account_id, roles }))
+}
+
+pub async fn revoke_role(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, String)>,
+) -> Result<HttpResponse, MyError> {
+    let (account_id, role) = params.into_inner();
+    let role: Role = role.parse()?;
+    // 防止管理员误操作后没有人能再管理角色
+    if account_id == user.account_id && role == Role::Admin {
+        return Err(MyError::InvalidInput("Cannot revoke your own admin role".into()));
+    }
+    app_state.db.revoke_role_db(&audit, account_id, role)
+        .await
+        .map(|roles| HttpResponse::Ok().json(AccountRoles { account_id, roles }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::auth::{AccountRoles, Role, TokenKind, TokenResponse};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error, SEED_PASSWORD};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn get_account_roles_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/accounts/1/roles")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let roles: AccountRoles = test::read_body_json(resp).await;
+        assert_eq!(roles.account_id, 1);
+        assert_eq!(roles.roles, vec![Role::Teacher]);
+    }
+
+    #[actix_rt::test]
+    async fn grant_and_revoke_role_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state.clone())).await;
+
+        let req = test::TestRequest::put()
+            .uri("/admin/accounts/2/roles/admin")
+            .insert_header(auth.clone())
+            .to_request();
+        let roles: AccountRoles = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roles.roles, vec![Role::Admin, Role::Teacher]);
+
+        // 授予已有的角色不会报错，也不会重复
+        let req = test::TestRequest::put()
+            .uri("/admin/accounts/2/roles/admin")
+            .insert_header(auth.clone())
+            .to_request();
+        let roles: AccountRoles = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roles.roles, vec![Role::Admin, Role::Teacher]);
+
+        // 重新登录后令牌中携带新的角色
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "username": "teacher_two", "password": SEED_PASSWORD }))
+            .to_request();
+        let tokens: TokenResponse = test::call_and_read_body_json(&app, req).await;
+        let claims = app_state.auth.decode_token(&tokens.access_token, TokenKind::Access).unwrap();
+        assert_eq!(claims.roles, vec![Role::Admin, Role::Teacher]);
+
+        let req = test::TestRequest::delete()
+            .uri("/admin/accounts/2/roles/teacher")
+            .insert_header(auth)
+            .to_request();
+        let roles: AccountRoles = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roles.roles, vec![Role::Admin]);
+    }
+
+    #[actix_rt::test]
+    async fn grant_role_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/admin/accounts/1/roles/superuser")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "request.invalid", "error_message": "Unknown role: superuser" })
+        );
+
+        let req = test::TestRequest::put()
+            .uri("/admin/accounts/100/roles/student")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::delete()
+            .uri("/admin/accounts/4/roles/admin")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "request.invalid", "error_message": "Cannot revoke your own admin role" })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn admin_routes_require_admin() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/admin/accounts/1/roles/admin")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get().uri("/admin/accounts/1/roles").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
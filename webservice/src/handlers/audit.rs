@@ -0,0 +1,121 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::errors::MyError;
+use crate::handlers::course::next_page_link;
+use crate::models::audit::{AuditPage, AuditQuery};
+use crate::state::AppState;
+
+/// 按实体过滤的审计日志，例如 `/audit?entity=course&id=1`
+pub async fn get_audit_log(
+    req: HttpRequest,
+    app_state: web::Data<AppState>,
+    query: web::Query<AuditQuery>,
+) -> Result<HttpResponse, MyError> {
+    let query = query.into_inner();
+    query.validate()?;
+
+    let (items, total) = app_state.db.get_audit_log_db(&query).await?;
+    let (limit, offset) = (query.limit(), query.offset());
+    Ok(HttpResponse::Ok().json(AuditPage {
+        total,
+        limit,
+        offset,
+        next: next_page_link(&req, limit, offset, items.len(), total),
+        items,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::REQUEST_ID_HEADER;
+    use crate::models::audit::{AuditAction, AuditEntity, AuditPage};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn course_changes_are_audited() {
+        let app_state = create_app_state().await;
+        let teacher = bearer(&app_state, 1);
+        let admin = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/1")
+            .insert_header(teacher.clone())
+            .insert_header((REQUEST_ID_HEADER, "audit-patch-1"))
+            .set_json(serde_json::json!({ "name": "Audited course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1")
+            .insert_header(teacher)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get()
+            .uri("/audit?entity=course&id=1")
+            .insert_header(admin)
+            .to_request();
+        let page: AuditPage = test::call_and_read_body_json(&app, req).await;
+
+        // 最新的记录排在前面
+        assert_eq!(page.total, 2);
+        let (deleted, updated) = (&page.items[0], &page.items[1]);
+        assert_eq!(deleted.action, AuditAction::Delete);
+        assert_eq!(deleted.before.as_ref().unwrap()["name"], "Audited course");
+        assert_eq!(deleted.after, None);
+
+        assert_eq!(updated.action, AuditAction::Update);
+        assert_eq!(updated.entity, AuditEntity::Course);
+        assert_eq!(updated.entity_id, 1);
+        assert_eq!(updated.actor_id, Some(1));
+        assert_eq!(updated.request_id.as_deref(), Some("audit-patch-1"));
+        // 只记录发生变化的字段
+        let before = updated.before.as_ref().unwrap();
+        let after = updated.after.as_ref().unwrap();
+        assert_eq!(after["name"], "Audited course");
+        assert_eq!(after["version"], 2);
+        assert_ne!(before["name"], after["name"]);
+        assert!(after.get("description").is_none());
+    }
+
+    #[actix_rt::test]
+    async fn get_audit_log_failure() {
+        let app_state = create_app_state().await;
+        let teacher = bearer(&app_state, 1);
+        let admin = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/audit?entity=course&id=1")
+            .insert_header(teacher)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri("/audit?entity=planet")
+            .insert_header(admin.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "entity", "message": "Unknown entity" }],
+            })
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/audit?id=1")
+            .insert_header(admin)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,385 @@
+use actix_web::{http::header, web, HttpResponse};
+use crate::auth::{hash_password, verify_password, AuthenticatedUser};
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::models::auth::{LoginRequest, RefreshRequest, RegisterTeacher, TokenKind};
+use crate::models::student::{CreateStudent, RegisterStudent};
+use crate::models::teacher::CreateTeacher;
+use crate::state::AppState;
+use crate::validation::Validator;
+
+/// 用户名的最大长度，与 account.username 列一致
+const MAX_USERNAME_LENGTH: usize = 100;
+/// 密码的最小长度
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// 检查注册用的用户名和密码，格式不对时返回 Validation，用户名已被占用时返回 UniqueViolation
+async fn validate_new_account(
+    app_state: &AppState,
+    username: &str,
+    password: &str,
+) -> Result<(), MyError> {
+    Validator::new()
+        .required("username", username, MAX_USERNAME_LENGTH)
+        .min_length("password", password, MIN_PASSWORD_LENGTH)
+        .finish()?;
+    match app_state.db.get_account_by_username_db(username).await {
+        Ok(_) => Err(MyError::UniqueViolation("Username already exists".into())),
+        Err(MyError::NotFound { .. }) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn register_teacher(
+    register: web::Json<RegisterTeacher>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+) -> Result<HttpResponse, MyError> {
+    let register: RegisterTeacher = register.into();
+    let username = register.username.trim();
+    validate_new_account(&app_state, username, &register.password).await?;
+
+    let password_hash = hash_password(&register.password)?;
+    let new_teacher = CreateTeacher {
+        name: register.name,
+        picture_url: register.picture_url,
+        profile: register.profile,
+    };
+    new_teacher.validator().finish()?;
+    app_state.db.register_teacher_db(&audit, new_teacher, username, &password_hash)
+        .await
+        .map(|teacher| {
+            HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/teachers/{}", teacher.id)))
+                .json(teacher)
+        })
+}
+
+pub async fn register_student(
+    register: web::Json<RegisterStudent>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+) -> Result<HttpResponse, MyError> {
+    let register: RegisterStudent = register.into();
+    let username = register.username.trim();
+    validate_new_account(&app_state, username, &register.password).await?;
+
+    let password_hash = hash_password(&register.password)?;
+    let new_student = CreateStudent {
+        name: register.name,
+        email: register.email,
+    };
+    app_state.db.register_student_db(&audit, new_student, username, &password_hash)
+        .await
+        .map(|student| {
+            HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/students/{}", student.id)))
+                .json(student)
+        })
+}
+
+pub async fn login(
+    login: web::Json<LoginRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, MyError> {
+    // 用户名不存在和密码错误返回相同的信息，避免泄露哪些账号存在
+    let account = match app_state.db.get_account_by_username_db(login.username.trim()).await {
+        Ok(account) => account,
+        Err(MyError::NotFound { .. }) => {
+            return Err(MyError::Unauthorized("Invalid username or password".into()))
+        }
+        Err(err) => return Err(err),
+    };
+    if !verify_password(&login.password, &account.password_hash) {
+        return Err(MyError::Unauthorized("Invalid username or password".into()));
+    }
+
+    let roles = app_state.db.get_account_roles_db(account.id).await?;
+    app_state.auth.issue_tokens(&account, &roles)
+        .map(|tokens| HttpResponse::Ok().json(tokens))
+}
+
+pub async fn refresh(
+    refresh: web::Json<RefreshRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, MyError> {
+    let claims = app_state.auth.decode_token(&refresh.refresh_token, TokenKind::Refresh)?;
+
+    // 重新读取账号和角色，已删除的账号不能继续刷新令牌，角色变更在刷新后生效
+    let account = app_state.db.get_account_db(claims.account_id)
+        .await
+        .map_err(|err| match err {
+            MyError::NotFound { .. } => MyError::Unauthorized("Invalid or expired token".into()),
+            err => err,
+        })?;
+
+    let roles = app_state.db.get_account_roles_db(account.id).await?;
+    app_state.auth.issue_tokens(&account, &roles)
+        .map(|tokens| HttpResponse::Ok().json(tokens))
+}
+
+/// 返回当前令牌对应的调用者身份
+pub async fn me(user: AuthenticatedUser) -> HttpResponse {
+    HttpResponse::Ok().json(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::MyError;
+    use crate::models::audit::AuditContext;
+    use crate::models::auth::{Role, TokenKind, TokenResponse};
+    use crate::models::student::Student;
+    use crate::models::teacher::{CreateTeacher, Teacher};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error, SEED_PASSWORD};
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, ResponseError};
+
+    #[actix_rt::test]
+    async fn register_teacher_success() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/register")
+            .set_json(serde_json::json!({
+                "username": "new_teacher",
+                "password": "a-long-password",
+                "name": "A New Teacher",
+                "picture_url": "https://example.com/new.jpg",
+                "profile": "This is a test profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/teachers/4");
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.name, "A New Teacher");
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "username": "new_teacher", "password": "a-long-password" }))
+            .to_request();
+        let tokens: TokenResponse = test::call_and_read_body_json(&app, req).await;
+
+        let req = test::TestRequest::get()
+            .uri("/auth/me")
+            .insert_header((header::AUTHORIZATION, format!("Bearer {}", tokens.access_token)))
+            .to_request();
+        let user: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(user["username"], "new_teacher");
+        assert_eq!(user["teacher_id"], 4);
+        assert_eq!(user["roles"], serde_json::json!(["teacher"]));
+    }
+
+    #[actix_rt::test]
+    async fn duplicate_username_is_a_unique_violation() {
+        let app_state = create_app_state().await;
+
+        // 跳过处理器中的预先检查，直接触发 account.username 的唯一约束
+        let new_teacher = CreateTeacher {
+            name: "Another Teacher".into(),
+            picture_url: "https://example.com/another.jpg".into(),
+            profile: "".into(),
+        };
+        let err = app_state
+            .db
+            .register_teacher_db(&AuditContext::default(), new_teacher, "teacher_one", "hash")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, MyError::UniqueViolation(_)));
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+        assert_eq!(err.code(), "db.unique_violation");
+    }
+
+    #[actix_rt::test]
+    async fn register_student_success() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state.clone())).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/register/student")
+            .set_json(serde_json::json!({
+                "username": "new_student",
+                "password": "a-long-password",
+                "name": "A New Student",
+                "email": "new@example.com",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/students/3");
+        let student: Student = test::read_body_json(resp).await;
+        assert_eq!(student.name, "A New Student");
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "username": "new_student", "password": "a-long-password" }))
+            .to_request();
+        let tokens: TokenResponse = test::call_and_read_body_json(&app, req).await;
+        let claims = app_state.auth.decode_token(&tokens.access_token, TokenKind::Access).unwrap();
+        assert_eq!(claims.student_id, Some(3));
+        assert_eq!(claims.teacher_id, None);
+        assert_eq!(claims.roles, vec![Role::Student]);
+    }
+
+    #[actix_rt::test]
+    async fn register_teacher_invalid_input() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/register")
+            .set_json(serde_json::json!({
+                "username": "teacher_one",
+                "password": "a-long-password",
+                "name": "Duplicate",
+                "picture_url": "https://example.com/dup.jpg",
+                "profile": "Duplicate profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(read_error(resp).await["code"], "db.unique_violation");
+
+        let req = test::TestRequest::post()
+            .uri("/auth/register")
+            .set_json(serde_json::json!({
+                "username": " ",
+                "password": "short",
+                "name": "Short",
+                "picture_url": "https://example.com/short.jpg",
+                "profile": "Short profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_error(resp).await;
+        assert_eq!(body["code"], "validation.failed");
+        assert_eq!(
+            body["details"],
+            serde_json::json!([
+                { "field": "username", "message": "must not be empty" },
+                { "field": "password", "message": "must be at least 8 characters" },
+            ])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn login_success() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state.clone())).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "username": "teacher_two", "password": SEED_PASSWORD }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let tokens: TokenResponse = test::read_body_json(resp).await;
+        assert_eq!(tokens.token_type, "Bearer");
+        let claims = app_state.auth.decode_token(&tokens.access_token, TokenKind::Access).unwrap();
+        assert_eq!(claims.account_id, 2);
+        assert_eq!(claims.teacher_id, Some(2));
+        assert!(app_state.auth.decode_token(&tokens.refresh_token, TokenKind::Refresh).is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn login_failure() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        for (username, password) in [("teacher_two", "wrong-password"), ("nobody", SEED_PASSWORD)] {
+            let req = test::TestRequest::post()
+                .uri("/auth/login")
+                .set_json(serde_json::json!({ "username": username, "password": password }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(
+                read_error(resp).await,
+                serde_json::json!({ "code": "auth.unauthorized", "error_message": "Invalid username or password" })
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    async fn refresh_success() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state.clone())).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(serde_json::json!({ "username": "teacher_one", "password": SEED_PASSWORD }))
+            .to_request();
+        let tokens: TokenResponse = test::call_and_read_body_json(&app, req).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(serde_json::json!({ "refresh_token": tokens.refresh_token }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let refreshed: TokenResponse = test::read_body_json(resp).await;
+        let claims = app_state.auth.decode_token(&refreshed.access_token, TokenKind::Access).unwrap();
+        assert_eq!(claims.username, "teacher_one");
+    }
+
+    #[actix_rt::test]
+    async fn refresh_rejects_access_token() {
+        let app_state = create_app_state().await;
+        let (_, authorization) = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let access_token = authorization.trim_start_matches("Bearer ");
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(serde_json::json!({ "refresh_token": access_token }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_rt::test]
+    async fn me_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 3);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/auth/me").insert_header(auth).to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let user: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(user["account_id"], 3);
+        assert_eq!(user["username"], "teacher_three");
+    }
+
+    #[actix_rt::test]
+    async fn protected_route_requires_token() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::delete().uri("/courses/1/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "auth.unauthorized", "error_message": "Missing bearer token" })
+        );
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/1")
+            .insert_header((header::AUTHORIZATION, "Bearer not-a-token"))
+            .set_json(serde_json::json!({ "name": "Hijacked" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "auth.unauthorized", "error_message": "Invalid or expired token" })
+        );
+    }
+}
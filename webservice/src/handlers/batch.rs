@@ -0,0 +1,315 @@
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, ResponseError};
+use crate::auth::AuthenticatedUser;
+use crate::dbaccess::unit_of_work::UnitOfWork;
+use crate::errors::{MyError, MyErrorResponse};
+use crate::models::audit::AuditContext;
+use crate::models::batch::{BatchOperation, BatchRequest, BatchResponse, BatchResult};
+use crate::models::course::Course;
+use crate::state::AppState;
+
+/// 批量新建、修改和删除课程；请求本身有效时总是返回 200，各操作的结果见 results
+pub async fn batch_courses(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    batch: web::Json<BatchRequest>,
+) -> Result<HttpResponse, MyError> {
+    let batch = batch.into_inner();
+    batch.validate()?;
+
+    let response = if batch.atomic {
+        run_atomic(&app_state, &user, &audit, batch.operations).await?
+    } else {
+        run_each(&app_state, &user, &audit, batch.operations).await?
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// 全部操作共用一个事务，遇到第一个失败的操作就停止并回滚
+async fn run_atomic(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    audit: &AuditContext,
+    operations: Vec<BatchOperation>,
+) -> Result<BatchResponse, MyError> {
+    let total = operations.len();
+    let mut uow = app_state.db.begin_db().await?;
+    let mut results = Vec::with_capacity(total);
+    for (index, operation) in operations.into_iter().enumerate() {
+        match apply(&mut *uow, user, audit, operation).await {
+            Ok(result) => results.push(result),
+            Err(err) => {
+                drop(uow);
+                let results = (0..total)
+                    .map(|other| if other == index { failure(&err) } else { aborted(index) })
+                    .collect();
+                return Ok(BatchResponse {
+                    atomic: true,
+                    committed: false,
+                    results,
+                });
+            }
+        }
+    }
+    uow.commit().await?;
+
+    Ok(BatchResponse {
+        atomic: true,
+        committed: true,
+        results,
+    })
+}
+
+/// 每个操作使用单独的事务，失败的操作不影响其他操作
+async fn run_each(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    audit: &AuditContext,
+    operations: Vec<BatchOperation>,
+) -> Result<BatchResponse, MyError> {
+    let mut results = Vec::with_capacity(operations.len());
+    for operation in operations {
+        let mut uow = app_state.db.begin_db().await?;
+        let result = match apply(&mut *uow, user, audit, operation).await {
+            Ok(result) => uow.commit().await.map(|_| result),
+            Err(err) => Err(err),
+        };
+        results.push(result.unwrap_or_else(|err| failure(&err)));
+    }
+
+    Ok(BatchResponse {
+        atomic: false,
+        committed: results.iter().any(|result| result.error.is_none()),
+        results,
+    })
+}
+
+/// 执行单个操作，检查与单独调用对应接口时相同
+async fn apply(
+    uow: &mut dyn UnitOfWork,
+    user: &AuthenticatedUser,
+    audit: &AuditContext,
+    operation: BatchOperation,
+) -> Result<BatchResult, MyError> {
+    user.ensure_owner(operation.teacher_id())?;
+    match operation {
+        BatchOperation::Create { course } => {
+            let mut validator = course.validator();
+            match uow.lock_teacher_db(course.teacher_id).await {
+                Ok(_) => {}
+                Err(MyError::NotFound { .. }) => validator = validator.add("teacher_id", "Teacher does not exist"),
+                Err(err) => return Err(err),
+            }
+            validator.finish()?;
+            let course = uow.post_new_course_db(audit, course.canonicalize()?).await?;
+            Ok(success(StatusCode::CREATED, Some(course)))
+        }
+        BatchOperation::Update {
+            teacher_id,
+            course_id,
+            course,
+            version,
+        } => {
+            let course = uow
+                .update_course_details_db(audit, teacher_id, course_id, course.canonicalize()?, version)
+                .await?;
+            Ok(success(StatusCode::OK, Some(course)))
+        }
+        BatchOperation::Delete {
+            teacher_id,
+            course_id,
+            version,
+        } => {
+            uow.delete_course_db(audit, teacher_id, course_id, version).await?;
+            Ok(success(StatusCode::NO_CONTENT, None))
+        }
+    }
+}
+
+fn success(status: StatusCode, course: Option<Course>) -> BatchResult {
+    BatchResult {
+        status: status.as_u16(),
+        course,
+        error: None,
+    }
+}
+
+fn failure(err: &MyError) -> BatchResult {
+    BatchResult {
+        status: err.status_code().as_u16(),
+        course: None,
+        error: Some(err.to_body(None)),
+    }
+}
+
+/// 原子模式下因为其他操作失败而没有生效的操作
+fn aborted(failed_index: usize) -> BatchResult {
+    BatchResult {
+        status: StatusCode::FAILED_DEPENDENCY.as_u16(),
+        course: None,
+        error: Some(MyErrorResponse {
+            code: "batch.aborted".into(),
+            error_message: format!("Not applied because operation {} failed", failed_index),
+            details: vec![],
+            request_id: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::batch::BatchResponse;
+    use crate::models::course::{Course, CoursePage};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn batch_atomic_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "operations": [
+                    { "op": "create", "course": { "teacher_id": 1, "name": "Batch course", "level": "beginner" } },
+                    { "op": "update", "teacher_id": 1, "course_id": 1, "course": { "price": null }, "version": 1 },
+                    { "op": "delete", "teacher_id": 1, "course_id": 2 },
+                ]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let batch: BatchResponse = test::read_body_json(resp).await;
+        assert!(batch.atomic);
+        assert!(batch.committed);
+        let statuses: Vec<u16> = batch.results.iter().map(|result| result.status).collect();
+        assert_eq!(statuses, vec![201, 200, 204]);
+        let created = batch.results[0].course.as_ref().unwrap();
+        assert_eq!(created.level.map(|level| level.to_string()).as_deref(), Some("Beginner"));
+        let updated = batch.results[1].course.as_ref().unwrap();
+        assert_eq!(updated.price, None);
+        assert_eq!(updated.version, 2);
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        let names: Vec<&str> = page.items.iter().map(|course: &Course| course.name.as_str()).collect();
+        assert_eq!(names, vec!["First course", "Batch course"]);
+    }
+
+    #[actix_rt::test]
+    async fn batch_atomic_rolls_back() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "atomic": true,
+                "operations": [
+                    { "op": "delete", "teacher_id": 1, "course_id": 2 },
+                    { "op": "update", "teacher_id": 1, "course_id": 1, "course": { "name": "Renamed" }, "version": 7 },
+                    { "op": "create", "course": { "teacher_id": 1, "name": "Never created" } },
+                ]
+            }))
+            .to_request();
+        let batch: BatchResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(!batch.committed);
+        let statuses: Vec<u16> = batch.results.iter().map(|result| result.status).collect();
+        assert_eq!(statuses, vec![424, 412, 424]);
+        let error = batch.results[1].error.as_ref().unwrap();
+        assert_eq!(error.code, "precondition.failed");
+        let error = batch.results[0].error.as_ref().unwrap();
+        assert_eq!(error.code, "batch.aborted");
+        assert_eq!(error.error_message, "Not applied because operation 1 failed");
+
+        // 第一个操作的删除也被回滚
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
+    }
+
+    #[actix_rt::test]
+    async fn batch_best_effort() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "atomic": false,
+                "operations": [
+                    { "op": "delete", "teacher_id": 1, "course_id": 2 },
+                    { "op": "delete", "teacher_id": 2, "course_id": 3 },
+                    { "op": "create", "course": { "teacher_id": 1, "name": "", "capacity": 0 } },
+                    { "op": "update", "teacher_id": 1, "course_id": 99, "course": { "name": "Missing" } },
+                    { "op": "delete", "teacher_id": 1, "course_id": 1 },
+                ]
+            }))
+            .to_request();
+        let batch: BatchResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(!batch.atomic);
+        assert!(batch.committed);
+        let statuses: Vec<u16> = batch.results.iter().map(|result| result.status).collect();
+        assert_eq!(statuses, vec![204, 403, 400, 404, 204]);
+        let error = batch.results[2].error.as_ref().unwrap();
+        assert_eq!(error.code, "validation.failed");
+        let fields: Vec<&str> = error.details.iter().map(|detail| detail.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "capacity"]);
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 0);
+        let req = test::TestRequest::get().uri("/courses/2").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+    }
+
+    #[actix_rt::test]
+    async fn batch_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state.clone())).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "operations": [] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "operations", "message": "must not be empty" }],
+            })
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "operations": [{ "op": "archive", "teacher_id": 1, "course_id": 1 }] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // 学生不能调用
+        let req = test::TestRequest::post()
+            .uri("/courses/batch")
+            .insert_header(bearer(&app_state, 5))
+            .set_json(serde_json::json!({ "operations": [{ "op": "delete", "teacher_id": 1, "course_id": 1 }] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+}
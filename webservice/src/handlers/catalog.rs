@@ -0,0 +1,313 @@
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::models::catalog::{
+    parse_courses, validate_courses, write_courses, CatalogFormat, ExportQuery, ImportQuery, ImportReport,
+};
+use crate::models::course::{CourseQuery, MAX_PAGE_SIZE};
+use crate::state::AppState;
+
+/// 从 CSV 或 JSON 文件导入课程，格式由 Content-Type 决定；全部通过校验才会在一个事务中写入
+pub async fn import_courses(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<i32>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, MyError> {
+    let teacher_id = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.get_teacher_details_db(teacher_id).await?;
+
+    let mime = req
+        .mime_type()
+        .map_err(|_err| MyError::InvalidInput("Invalid Content-Type".into()))?;
+    let format = CatalogFormat::from_mime(mime.as_ref().map(|mime| mime.essence_str()))?;
+    let rows = parse_courses(format, &body)?;
+    let total = rows.len();
+    let (courses, errors) = validate_courses(teacher_id, rows);
+
+    // 试运行只报告错误，不写入
+    if query.dry_run() {
+        return Ok(HttpResponse::Ok().json(ImportReport {
+            dry_run: true,
+            total,
+            errors,
+            courses: vec![],
+        }));
+    }
+    if !errors.is_empty() {
+        return Err(MyError::Validation(errors));
+    }
+
+    let mut uow = app_state.db.begin_db().await?;
+    let mut imported = Vec::with_capacity(courses.len());
+    for course in courses {
+        imported.push(uow.post_new_course_db(&audit, course).await?);
+    }
+    uow.commit().await?;
+
+    Ok(HttpResponse::Created().json(ImportReport {
+        dry_run: false,
+        total,
+        errors: vec![],
+        courses: imported,
+    }))
+}
+
+/// 导出教师的全部课程，列与导入时相同
+pub async fn export_courses(
+    app_state: web::Data<AppState>,
+    params: web::Path<i32>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, MyError> {
+    let teacher_id = params.into_inner();
+    let format = query.format()?;
+    app_state.db.get_teacher_details_db(teacher_id).await?;
+
+    let mut courses = vec![];
+    loop {
+        let page_query = CourseQuery {
+            limit: Some(MAX_PAGE_SIZE),
+            offset: Some(courses.len() as i64),
+            ..Default::default()
+        };
+        let (page, total) = app_state.db.get_courses_for_teacher_db(teacher_id, &page_query).await?;
+        let done = page.is_empty() || courses.len() + page.len() >= total as usize;
+        courses.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!(
+            "courses-{}.{}",
+            teacher_id,
+            format.as_str()
+        ))],
+    };
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((header::CONTENT_DISPOSITION, disposition))
+        .body(write_courses(format, courses)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::catalog::ImportReport;
+    use crate::models::course::{Course, CoursePage, MAX_DESCRIPTION_LENGTH};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::{header, StatusCode};
+    use actix_web::test;
+
+    const CSV: &str = "teacher_id,name,time,description,format,price,language,level,capacity
+2,Imported one,2025-08-01T09:00:00,From a spreadsheet,video,80,english,beginner,
+2,Imported two,,,,,,,30
+";
+
+    #[actix_rt::test]
+    async fn import_csv_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/2/import")
+            .insert_header(auth)
+            .insert_header((header::CONTENT_TYPE, "text/csv"))
+            .set_payload(CSV)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let report: ImportReport = test::read_body_json(resp).await;
+        assert_eq!(report.total, 2);
+        assert!(report.errors.is_empty());
+        // format、language、level 按规范值保存
+        let course = &report.courses[0];
+        assert_eq!(course.name, "Imported one");
+        assert_eq!(course.format.map(|format| format.to_string()).as_deref(), Some("Video"));
+        assert_eq!(course.price, Some(80));
+        assert_eq!(report.courses[1].capacity, Some(30));
+        assert_eq!(report.courses[1].description, None);
+
+        let req = test::TestRequest::get().uri("/courses/2").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 3);
+    }
+
+    #[actix_rt::test]
+    async fn import_dry_run_and_rollback() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+        let rows = serde_json::json!([
+            { "teacher_id": 1, "name": "Valid course" },
+            { "teacher_id": 2, "name": "", "price": -1 },
+            { "teacher_id": 1, "name": "Bad level", "level": "Expert" },
+            { "teacher_id": 1 },
+        ]);
+
+        // 试运行报告每一行的错误，不写入任何课程
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import?dry_run=true")
+            .insert_header(auth.clone())
+            .set_json(&rows)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let report: ImportReport = test::read_body_json(resp).await;
+        assert!(report.dry_run);
+        assert_eq!(report.total, 4);
+        assert!(report.courses.is_empty());
+        let fields: Vec<&str> = report.errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["rows[1].name", "rows[1].price", "rows[1].teacher_id", "rows[2].level", "rows[3]"]
+        );
+        assert_eq!(report.errors[4].message, "missing field `name`");
+
+        // 有错误时整批拒绝，有效的行也不会写入
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import")
+            .insert_header(auth.clone())
+            .set_json(&rows)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_error(resp).await;
+        assert_eq!(body["code"], "validation.failed");
+        assert_eq!(body["details"][0]["field"], "rows[1].name");
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
+
+        // CSV 中无法解析的值定位到具体的列
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import?dry_run=true")
+            .insert_header(auth)
+            .insert_header((header::CONTENT_TYPE, "text/csv"))
+            .set_payload("teacher_id,name,price\n1,Cheap,free\n")
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].field, "rows[0].price");
+    }
+
+    #[actix_rt::test]
+    async fn import_large_file() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 超过 actix 默认 256 KiB 的请求体上限
+        let description = "长".repeat(MAX_DESCRIPTION_LENGTH);
+        let rows: Vec<serde_json::Value> = (0..100)
+            .map(|row| serde_json::json!({ "teacher_id": 1, "name": format!("Course {}", row), "description": description }))
+            .collect();
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import?dry_run=true")
+            .insert_header(auth)
+            .set_json(&rows)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let report: ImportReport = test::read_body_json(resp).await;
+        assert_eq!(report.total, 100);
+        assert!(report.errors.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn import_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/2/import")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!([{ "teacher_id": 2, "name": "Not mine" }]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import")
+            .insert_header(auth.clone())
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .set_payload("name\nPlain text")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "request.invalid",
+                "error_message": "Content-Type must be text/csv or application/json",
+            })
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/import")
+            .insert_header(auth)
+            .set_json(serde_json::json!([]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(read_error(resp).await["error_message"], "No courses to import");
+    }
+
+    #[actix_rt::test]
+    async fn export_and_reimport() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/export?format=csv").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv; charset=utf-8");
+        assert_eq!(
+            resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"courses-1.csv\""
+        );
+        let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("teacher_id,name,time,description,format,price,language,level,capacity"));
+        assert_eq!(
+            lines.next(),
+            Some("1,First course,2025-07-12T10:15:00,Course one of teacher one,Video,100,English,Beginner,")
+        );
+        assert_eq!(lines.count(), 1);
+
+        // 导出的 JSON 改成另一位教师后可以直接导入
+        let req = test::TestRequest::get().uri("/courses/1/export").to_request();
+        let mut rows: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(rows.len(), 2);
+        for row in rows.iter_mut() {
+            row["teacher_id"] = serde_json::json!(2);
+        }
+        let req = test::TestRequest::post()
+            .uri("/courses/2/import")
+            .insert_header(auth)
+            .set_json(&rows)
+            .to_request();
+        let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+        let names: Vec<&str> = report.courses.iter().map(|course: &Course| course.name.as_str()).collect();
+        assert_eq!(names, vec!["First course", "Second course"]);
+
+        let req = test::TestRequest::get().uri("/courses/1/export?format=xml").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::get().uri("/courses/99/export").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
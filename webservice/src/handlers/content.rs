@@ -0,0 +1,384 @@
+use actix_web::{http::header, web, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::models::content::{CreateLesson, CreateModule, Reorder, UpdateLesson, UpdateModule};
+use crate::state::AppState;
+
+/// 课程大纲：按顺序列出全部章节及其课时
+pub async fn get_course_modules(
+    app_state: web::Data<AppState>,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    app_state.db.get_course_modules_db(teacher_id, course_id)
+        .await
+        .map(|modules| HttpResponse::Ok().json(modules))
+}
+
+pub async fn get_module_detail(
+    app_state: web::Data<AppState>,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id) = params.into_inner();
+    app_state.db.get_module_db(teacher_id, course_id, module_id)
+        .await
+        .map(|module| HttpResponse::Ok().json(module))
+}
+
+pub async fn post_new_module(
+    user: AuthenticatedUser,
+    new_module: web::Json<CreateModule>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.post_new_module_db(&audit, teacher_id, course_id, new_module.into())
+        .await
+        .map(|module| {
+            HttpResponse::Created()
+                .insert_header((
+                    header::LOCATION,
+                    format!("/courses/{}/{}/modules/{}", teacher_id, course_id, module.id),
+                ))
+                .json(module)
+        })
+}
+
+pub async fn update_module(
+    user: AuthenticatedUser,
+    update_module: web::Json<UpdateModule>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.update_module_db(&audit, teacher_id, course_id, module_id, update_module.into())
+        .await
+        .map(|module| HttpResponse::Ok().json(module))
+}
+
+pub async fn delete_module(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.delete_module_db(&audit, teacher_id, course_id, module_id)
+        .await
+        .map(|_| HttpResponse::NoContent().finish())
+}
+
+/// 按请求中 id 的顺序重新排列课程的全部章节
+pub async fn reorder_modules(
+    user: AuthenticatedUser,
+    reorder: web::Json<Reorder>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.reorder_modules_db(&audit, teacher_id, course_id, &reorder.ids)
+        .await
+        .map(|modules| HttpResponse::Ok().json(modules))
+}
+
+pub async fn post_new_lesson(
+    user: AuthenticatedUser,
+    new_lesson: web::Json<CreateLesson>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.post_new_lesson_db(&audit, teacher_id, course_id, module_id, new_lesson.try_into()?)
+        .await
+        .map(|lesson| {
+            HttpResponse::Created()
+                .insert_header((
+                    header::LOCATION,
+                    format!(
+                        "/courses/{}/{}/modules/{}/lessons/{}",
+                        teacher_id, course_id, module_id, lesson.id
+                    ),
+                ))
+                .json(lesson)
+        })
+}
+
+pub async fn update_lesson(
+    user: AuthenticatedUser,
+    update_lesson: web::Json<UpdateLesson>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id, lesson_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.update_lesson_db(&audit, teacher_id, course_id, module_id, lesson_id, update_lesson.try_into()?)
+        .await
+        .map(|lesson| HttpResponse::Ok().json(lesson))
+}
+
+pub async fn delete_lesson(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id, lesson_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.delete_lesson_db(&audit, teacher_id, course_id, module_id, lesson_id)
+        .await
+        .map(|_| HttpResponse::NoContent().finish())
+}
+
+/// 按请求中 id 的顺序重新排列章节的全部课时
+pub async fn reorder_lessons(
+    user: AuthenticatedUser,
+    reorder: web::Json<Reorder>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, module_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.reorder_lessons_db(&audit, teacher_id, course_id, module_id, &reorder.ids)
+        .await
+        .map(|module| HttpResponse::Ok().json(module))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::content::{CourseModule, Lesson, ModuleOutline};
+    use crate::models::course::Course;
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::{header, StatusCode};
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn get_course_modules_success() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/1/modules").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let modules: Vec<ModuleOutline> = test::read_body_json(resp).await;
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].module.title, "Getting started");
+        assert_eq!(modules[0].duration, 75);
+        let titles: Vec<&str> = modules[0].lessons.iter().map(|lesson| lesson.title.as_str()).collect();
+        assert_eq!(titles, ["Installation", "Hello world"]);
+        assert_eq!(modules[1].duration, 15);
+
+        // 课程的总时长由课时计算
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.duration, 90);
+    }
+
+    #[actix_rt::test]
+    async fn get_course_modules_failure() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/2/1/modules").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // 章节 1 属于课程 1，不能通过课程 2 访问
+        let req = test::TestRequest::get().uri("/courses/1/2/modules/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "module.not_found", "error_message": "Module Id not found" })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn post_new_module_and_lesson_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/modules")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "title": "Wrapping up" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/courses/1/1/modules/3");
+        let module: CourseModule = test::read_body_json(resp).await;
+        assert_eq!(module.position, 3);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/modules/3/lessons")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "title": "Summary", "estimated_minutes": 20 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            "/courses/1/1/modules/3/lessons/4"
+        );
+        let lesson: Lesson = test::read_body_json(resp).await;
+        assert_eq!(lesson.position, 1);
+        assert_eq!(lesson.body, None);
+
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.duration, 110);
+    }
+
+    #[actix_rt::test]
+    async fn post_new_module_forbidden() {
+        let app_state = create_app_state().await;
+        let teacher_two = bearer(&app_state, 2);
+        let student_one = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        for auth in [teacher_two, student_one] {
+            let req = test::TestRequest::post()
+                .uri("/courses/1/1/modules")
+                .insert_header(auth)
+                .set_json(serde_json::json!({ "title": "Not mine" }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn update_and_delete_lesson_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/modules/1/lessons/2")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "estimated_minutes": 60, "attachment_url": null }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let lesson: Lesson = test::read_body_json(resp).await;
+        assert_eq!(lesson.title, "Hello world");
+        assert_eq!(lesson.estimated_minutes, 60);
+        assert_eq!(lesson.attachment_url, None);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/modules/1/lessons/1")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/courses/1/1/modules/1").to_request();
+        let module: ModuleOutline = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(module.lessons.len(), 1);
+        assert_eq!(module.duration, 60);
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/modules/1/lessons/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "estimated_minutes": -5 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn delete_module_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/modules/1")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.duration, 15);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/modules/1")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn reorder_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/modules/order")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "ids": [2, 1] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let modules: Vec<ModuleOutline> = test::read_body_json(resp).await;
+        let ids: Vec<i32> = modules.iter().map(|module| module.module.id).collect();
+        assert_eq!(ids, [2, 1]);
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/modules/1/lessons/order")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "ids": [2, 1] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let module: ModuleOutline = test::read_body_json(resp).await;
+        let ids: Vec<i32> = module.lessons.iter().map(|lesson| lesson.id).collect();
+        assert_eq!(ids, [2, 1]);
+        assert_eq!(module.lessons[0].position, 1);
+    }
+
+    #[actix_rt::test]
+    async fn reorder_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 缺少章节、重复的 id 和其他章节的课时都会被拒绝
+        let cases = [
+            ("/courses/1/1/modules/order", serde_json::json!({ "ids": [1] })),
+            ("/courses/1/1/modules/order", serde_json::json!({ "ids": [1, 1, 2] })),
+            ("/courses/1/1/modules/1/lessons/order", serde_json::json!({ "ids": [1, 3] })),
+        ];
+        for (uri, body) in cases {
+            let req = test::TestRequest::put()
+                .uri(uri)
+                .insert_header(auth.clone())
+                .set_json(body)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let req = test::TestRequest::get().uri("/courses/1/1/modules").to_request();
+        let modules: Vec<ModuleOutline> = test::call_and_read_body_json(&app, req).await;
+        let ids: Vec<i32> = modules.iter().map(|module| module.module.id).collect();
+        assert_eq!(ids, [1, 2]);
+    }
+}
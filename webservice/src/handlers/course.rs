@@ -1,201 +1,1059 @@
+use crate::auth::AuthenticatedUser;
 use crate::state::AppState;
-use crate::dbaccess::course::*;
 use crate::errors::MyError;
-use crate::models::course::{CreateCourse, UpdateCourse};
-use actix_web::{web, HttpResponse};
+use crate::models::audit::AuditContext;
+use crate::models::course::{Course, CoursePage, CourseQuery, CreateCourse, ReplaceCourse, UpdateCourse};
+use crate::models::course_option::CourseOptions;
+use crate::precondition::{check_if_match, conditional_get, etag, has_if_match};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
 
 pub async fn post_new_course(
+    user: AuthenticatedUser,
     new_course: web::Json<CreateCourse>,
     app_state: web::Data<AppState>,
+    audit: AuditContext,
 ) -> Result<HttpResponse, MyError> {
-    post_new_course_db(&app_state.db, new_course.try_into()?)
+    let new_course = new_course.into_inner();
+    user.ensure_owner(new_course.teacher_id)?;
+
+    // 字段校验和教师是否存在的检查一起返回
+    let mut validator = new_course.validator();
+    match app_state.db.get_teacher_details_db(new_course.teacher_id).await {
+        Ok(_) => {}
+        Err(MyError::NotFound { .. }) => validator = validator.add("teacher_id", "Teacher does not exist"),
+        Err(err) => return Err(err),
+    }
+    validator.finish()?;
+
+    app_state.db.post_new_course_db(&audit, new_course.canonicalize()?)
         .await
-        .map(|_| HttpResponse::Ok().json("Post new course successfully."))
+        .map(|course| {
+            HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/courses/{}/{}", course.teacher_id, course.id)))
+                .json(course)
+        })
 }
 
 pub async fn get_courses_for_teacher(
+    req: HttpRequest,
     app_state: web::Data<AppState>,
     params: web::Path<i32>,
+    query: web::Query<CourseQuery>,
 ) -> Result<HttpResponse, MyError> {
     let teacher_id = params.into_inner();
-    get_courses_for_teacher_db(&app_state.db, teacher_id)
+    let query = query.into_inner();
+    query.validate()?;
+
+    app_state.db.get_courses_for_teacher_db(teacher_id, &query)
         .await
-        .map(|courses| HttpResponse::Ok().json(courses))
+        .map(|(courses, total)| HttpResponse::Ok().json(course_page(&req, &query, courses, total)))
+}
+
+pub async fn search_courses(
+    req: HttpRequest,
+    app_state: web::Data<AppState>,
+    query: web::Query<CourseQuery>,
+) -> Result<HttpResponse, MyError> {
+    let query = query.into_inner();
+    query.validate()?;
+
+    app_state.db.search_courses_db(&query)
+        .await
+        .map(|(courses, total)| HttpResponse::Ok().json(course_page(&req, &query, courses, total)))
+}
+
+/// 组装分页结果
+fn course_page(req: &HttpRequest, query: &CourseQuery, items: Vec<Course>, total: i64) -> CoursePage {
+    let (limit, offset) = (query.limit(), query.offset());
+    CoursePage {
+        total,
+        limit,
+        offset,
+        next: next_page_link(req, limit, offset, items.len(), total),
+        items,
+    }
+}
+
+/// 还有剩余记录时根据当前请求生成下一页的链接，课程和评价列表共用
+pub(crate) fn next_page_link(
+    req: &HttpRequest,
+    limit: i64,
+    offset: i64,
+    returned: usize,
+    total: i64,
+) -> Option<String> {
+    let next_offset = offset + returned as i64;
+    if returned == 0 || next_offset >= total {
+        return None;
+    }
+
+    // 保留原有的过滤和排序参数，只替换 limit 和 offset
+    let mut params: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .filter(|param| !param.starts_with("limit=") && !param.starts_with("offset="))
+        .collect();
+    let paging = format!("limit={}&offset={}", limit, next_offset);
+    params.push(&paging);
+    Some(format!("{}?{}", req.path(), params.join("&")))
+}
+
+/// 课程难度、授课形式和授课语言的全部可选值，供前端填充下拉框
+pub async fn get_course_options() -> HttpResponse {
+    HttpResponse::Ok().json(CourseOptions::all())
 }
 
 pub async fn get_course_detail(
+    req: HttpRequest,
     app_state: web::Data<AppState>,
     params: web::Path<(i32, i32)>,
 ) -> Result<HttpResponse, MyError> {
     let (teacher_id, course_id) = params.into_inner();
-    get_course_details_db(&app_state.db, teacher_id, course_id)
+    app_state.db.get_course_details_db(teacher_id, course_id)
         .await
-        .map(|course| HttpResponse::Ok().json(course))
+        .map(|course| conditional_get(&req, course.version, &course))
+}
+
+/// 请求带有 If-Match 时与课程当前的 ETag 比较，返回写入时需要再次核对的版本号
+async fn if_match_course(
+    req: &HttpRequest,
+    app_state: &AppState,
+    teacher_id: i32,
+    course_id: i32,
+) -> Result<Option<i32>, MyError> {
+    if !has_if_match(req) {
+        return Ok(None);
+    }
+    let course = app_state.db.get_course_details_db(teacher_id, course_id).await?;
+    check_if_match(req, &etag(course.version, &course))?;
+    Ok(Some(course.version))
+}
+
+/// 修改后的课程，附带新的 ETag
+fn course_response(course: Course) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ETag(etag(course.version, &course)))
+        .json(course)
 }
 
 pub async fn update_course_detail(
+    req: HttpRequest,
+    user: AuthenticatedUser,
     app_state: web::Data<AppState>,
+    audit: AuditContext,
+    replace_course: web::Json<ReplaceCourse>,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    let update_course = replace_course.try_into()?;
+    let expected_version = if_match_course(&req, &app_state, teacher_id, course_id).await?;
+    app_state.db.update_course_details_db(&audit, teacher_id, course_id, update_course, expected_version)
+        .await
+        .map(course_response)
+}
+
+pub async fn patch_course_detail(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
     update_course: web::Json<UpdateCourse>,
     params: web::Path<(i32, i32)>,
 ) -> Result<HttpResponse, MyError> {
     let (teacher_id, course_id) = params.into_inner();
-    update_course_details_db(&app_state.db, teacher_id, course_id, update_course.into())
+    user.ensure_owner(teacher_id)?;
+    let update_course = update_course.try_into()?;
+    let expected_version = if_match_course(&req, &app_state, teacher_id, course_id).await?;
+    app_state.db.update_course_details_db(&audit, teacher_id, course_id, update_course, expected_version)
         .await
-        .map(|msg| HttpResponse::Ok().json(msg))
+        .map(course_response)
 }
 
 pub async fn delete_course(
+    req: HttpRequest,
+    user: AuthenticatedUser,
     app_state: web::Data<AppState>,
+    audit: AuditContext,
     params: web::Path<(i32, i32)>,
 ) -> Result<HttpResponse, MyError> {
     let (teacher_id, course_id) = params.into_inner();
-    delete_course_db(&app_state.db, teacher_id, course_id)
+    user.ensure_owner(teacher_id)?;
+    let expected_version = if_match_course(&req, &app_state, teacher_id, course_id).await?;
+    app_state.db.delete_course_db(&audit, teacher_id, course_id, expected_version)
         .await
-        .map(|msg| HttpResponse::Ok().json(msg))
+        .map(|_| HttpResponse::NoContent().finish())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::audit::AuditQuery;
+    use crate::models::content::CourseModule;
+    use crate::models::course_option::{CourseFormat, CourseLanguage, CourseLevel, CourseOptions};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error, seed_user};
     use actix_web::http::StatusCode;
-    use std::sync::Mutex;
-    use dotenv::dotenv;
-    use sqlx::mysql::MySqlPoolOptions;
-    use std::env;
-    use actix_web::ResponseError;
+    use actix_web::{test, ResponseError};
     use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
-    use sqlx::MySqlPool;
 
-    async fn create_db_pool() -> MySqlPool {
-        // 检测并读取 .env 文件中的内容，若不存在也会跳过异常
-        dotenv().ok();
+    #[actix_rt::test]
+    async fn post_new_course_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 模拟添加课程的请求
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "teacher_id": 2,
+                "name": "Test course",
+                "time": "2025-07-12T10:15:00",
+                "description": "This is a course",
+                "language": "English",
+                "level": "Beginner",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
 
-        let db_url = env::var("DATABASE_URL")
-            .expect("DATABASE_URL 没有在 .env 文件里设置");
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/courses/2/4");
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.id, 4);
+        assert_eq!(course.teacher_id, 2);
+        assert_eq!(course.name, "Test course");
+        assert_eq!(course.description.as_deref(), Some("This is a course"));
+        assert_eq!(
+            course.time,
+            Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2025, 7, 12).expect("Unknown date"),
+                NaiveTime::from_hms_opt(10, 15, 0).expect("Unknown time"),
+            ))
+        );
 
-        // 创建数据库连接池
-        MySqlPoolOptions::new()
-            .connect(&db_url)
-            .await
-            .unwrap()
+        let req = test::TestRequest::get().uri("/courses/2").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
     }
 
-    async fn create_app_state() -> web::Data<AppState> {
-        let db_pool = create_db_pool().await;
+    #[actix_rt::test]
+    async fn post_new_course_invalid_json() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
 
-        web::Data::new(AppState {
-            health_check_response: "".to_string(),
-            visit_count: Mutex::new(0),
-            db: db_pool,
-        })
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Course without teacher" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "teacher_id", "message": "missing field `teacher_id`" }],
+            })
+        );
     }
 
-    #[ignore]
     #[actix_rt::test]
-    async fn post_new_course_success() {
+    async fn post_new_course_reports_every_invalid_field() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
 
-        let course = web::Json(CreateCourse {
-            teacher_id: 1,
-            name: "Test course".into(),
-            time: Some(NaiveDateTime::new(
-                NaiveDate::from_ymd_opt(2025, 7, 12).expect("Unknown date"),
-                NaiveTime::from_hms_opt(10, 15, 0).expect("Unknown time"),
-            )),
-            description: Some("This is a course".into()),
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "teacher_id": 99,
+                "name": "  ",
+                "description": "x".repeat(2001),
+                "price": -1,
+                "capacity": 0,
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error_message"], "Validation failed");
+        assert_eq!(
+            body["details"],
+            serde_json::json!([
+                { "field": "name", "message": "must not be empty" },
+                { "field": "description", "message": "must be at most 2000 characters" },
+                { "field": "price", "message": "must be at least 0" },
+                { "field": "capacity", "message": "must be at least 1" },
+                { "field": "teacher_id", "message": "Teacher does not exist" },
+            ])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn update_course_rejects_invalid_fields() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/1")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "", "price": -5 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["details"],
+            serde_json::json!([
+                { "field": "name", "message": "must not be empty" },
+                { "field": "price", "message": "must be at least 0" },
+            ])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn get_course_detail_etag_and_not_modified() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+
+        let req = test::TestRequest::get()
+            .uri("/courses/1/1")
+            .insert_header((header::IF_NONE_MATCH, etag.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers().get(header::ETAG), Some(&etag));
+        assert!(test::read_body(resp).await.is_empty());
+
+        let req = test::TestRequest::get()
+            .uri("/courses/1/1")
+            .insert_header((header::IF_NONE_MATCH, "\"stale\""))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn patch_course_if_match() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.version, 1);
+
+        // 带着当前 ETag 修改成功，版本号加一并返回新的 ETag
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/1")
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, etag.clone()))
+            .set_json(serde_json::json!({ "name": "Renamed course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let new_etag = resp.headers().get(header::ETAG).unwrap().clone();
+        assert_ne!(new_etag, etag);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.version, 2);
+
+        // 旧的 ETag 已经过期，修改和删除都返回 412
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1")
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, etag.clone()))
+            .set_json(serde_json::json!({ "name": "Lost update" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "precondition.failed",
+                "error_message": "Resource has been modified; fetch it again and retry",
+            })
+        );
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1")
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1")
+            .insert_header(auth)
+            .insert_header((header::IF_MATCH, new_etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[actix_rt::test]
+    async fn update_course_db_version_mismatch() {
+        let app_state = create_app_state().await;
+
+        let update = UpdateCourse {
+            name: Some("Renamed course".into()),
+            ..Default::default()
+        };
+        let err = app_state.db.update_course_details_db(&AuditContext::default(), 1, 1, update, Some(7)).await.unwrap_err();
+        assert!(matches!(err, MyError::PreconditionFailed(_)));
+
+        let err = app_state.db.delete_course_db(&AuditContext::default(), 1, 1, Some(7)).await.unwrap_err();
+        assert!(matches!(err, MyError::PreconditionFailed(_)));
+        app_state.db.delete_course_db(&AuditContext::default(), 1, 1, Some(1)).await.unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn unit_of_work_commit_and_rollback() {
+        let app_state = create_app_state().await;
+        let ctx = AuditContext::default();
+        let audit_query = AuditQuery {
+            entity: Some("course".into()),
+            id: Some(1),
+            ..Default::default()
+        };
+
+        // 没有提交就丢弃：修改和审计日志都回滚
+        let mut uow = app_state.db.begin_db().await.unwrap();
+        let update = UpdateCourse {
+            name: Some("Never saved".into()),
+            ..Default::default()
+        };
+        uow.update_course_details_db(&ctx, 1, 1, update, None).await.unwrap();
+        uow.delete_course_db(&ctx, 1, 2, None).await.unwrap();
+        drop(uow);
+
+        let course = app_state.db.get_course_details_db(1, 1).await.unwrap();
+        assert_eq!(course.name, "First course");
+        assert_eq!(course.version, 1);
+        app_state.db.get_course_details_db(1, 2).await.unwrap();
+        let (entries, total) = app_state.db.get_audit_log_db(&audit_query).await.unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(total, 0);
+
+        // 在同一个事务中修改课程并把同一教师的另一门课程移入回收站
+        let mut uow = app_state.db.begin_db().await.unwrap();
+        let locked = uow.lock_course_db(1, 1).await.unwrap();
+        let update = UpdateCourse {
+            name: Some(format!("{} (v{})", locked.name, locked.version + 1)),
+            ..Default::default()
+        };
+        let course = uow.update_course_details_db(&ctx, 1, 1, update, Some(locked.version)).await.unwrap();
+        uow.delete_course_db(&ctx, 1, 2, None).await.unwrap();
+        uow.commit().await.unwrap();
+
+        assert_eq!(course.name, "First course (v2)");
+        assert_eq!(app_state.db.get_course_details_db(1, 1).await.unwrap().version, 2);
+        let err = app_state.db.get_course_details_db(1, 2).await.unwrap_err();
+        assert!(matches!(err, MyError::NotFound { .. }));
+        let (entries, _total) = app_state.db.get_audit_log_db(&audit_query).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].after.as_ref().unwrap()["name"], "First course (v2)");
+
+        let err = app_state.db.begin_db().await.unwrap().lock_course_db(1, 2).await.unwrap_err();
+        assert!(matches!(err, MyError::NotFound { .. }));
+    }
+
+    #[actix_rt::test]
+    async fn post_new_course_db_unknown_teacher() {
+        let app_state = create_app_state().await;
+
+        let new_course = CreateCourse {
+            teacher_id: 99,
+            name: "Orphan course".into(),
+            time: None,
+            description: None,
             format: None,
-            structure: None,
-            duration: None,
             price: None,
-            language: Some("English".into()),
-            level: Some("Beginner".into()),
-        });
+            language: None,
+            level: None,
+            capacity: None,
+        };
+        let err = app_state.db.post_new_course_db(&AuditContext::default(), new_course).await.unwrap_err();
 
-        // 模拟添加课程的请求
-        let response = post_new_course(course, app_state).await.unwrap();
-
-        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(err, MyError::ForeignKeyViolation(_)));
+        assert_eq!(err.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
     #[actix_rt::test]
     async fn get_all_courses_success() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: CoursePage = test::read_body_json(resp).await;
+        let names: Vec<&str> = page.items.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["First course", "Second course"]);
+        assert!(page.items.iter().all(|c| c.teacher_id == 1));
+        assert_eq!(page.total, 2);
+        assert_eq!(page.next, None);
+    }
+
+    #[actix_rt::test]
+    async fn get_courses_paginated() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1?sort=name&limit=1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
+        assert_eq!(page.limit, 1);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].name, "First course");
+        assert_eq!(page.next.as_deref(), Some("/courses/1?sort=name&limit=1&offset=1"));
+
+        let req = test::TestRequest::get().uri(page.next.as_deref().unwrap()).to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.offset, 1);
+        assert_eq!(page.items[0].name, "Second course");
+        assert_eq!(page.next, None);
+    }
+
+    #[actix_rt::test]
+    async fn get_courses_filtered_and_sorted() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1?sort=-price").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        let prices: Vec<Option<i32>> = page.items.iter().map(|c| c.price).collect();
+        assert_eq!(prices, vec![Some(200), Some(100)]);
+
+        let req = test::TestRequest::get()
+            .uri("/courses/1?format=Live&min_price=150&time_from=2025-07-13T00:00:00")
+            .to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, 2);
+
+        let req = test::TestRequest::get()
+            .uri("/courses/1?level=Beginner&time_to=2025-07-12T10:15:00")
+            .to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, 1);
+    }
+
+    #[actix_rt::test]
+    async fn get_courses_invalid_query() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1?sort=description").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "request.invalid", "error_message": "Unknown sort field: description" })
+        );
+
+        let req = test::TestRequest::get().uri("/courses/1?limit=0").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::get().uri("/courses/1?min_price=cheap").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "request.invalid", "error_message": "Please provide valid query parameters" })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn search_courses_success() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        // 不带关键字时浏览所有教师的课程
+        let req = test::TestRequest::get().uri("/courses").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 3);
+        let ids: Vec<i32> = page.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // 同时命中两个关键字的课程排在最前面
+        let req = test::TestRequest::get().uri("/courses?q=third%20teacher").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].id, 3);
+
+        let req = test::TestRequest::get().uri("/courses?q=Second").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].name, "Second course");
+
+        let req = test::TestRequest::get().uri("/courses?q=course&language=Chinese").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, 3);
+    }
+
+    #[actix_rt::test]
+    async fn search_courses_matches_content() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 章节标题和课时标题都参与检索，同一课程只出现一次
+        let req = test::TestRequest::get().uri("/courses?q=installation%20further").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, 1);
+
+        // 新增、修改的章节立即可以检索到
+        let req = test::TestRequest::post()
+            .uri("/courses/2/3/modules")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "title": "Kubernetes basics" }))
+            .to_request();
+        let module: CourseModule = test::call_and_read_body_json(&app, req).await;
+        let req = test::TestRequest::get().uri("/courses?q=kubernetes").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        let ids: Vec<i32> = page.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![3]);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/courses/2/3/modules/{}", module.id))
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "title": "Container basics" }))
+            .to_request();
+        test::call_service(&app, req).await;
+        let req = test::TestRequest::get().uri("/courses?q=kubernetes").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 0);
+
+        // 删除章节后不再命中
+        let req = test::TestRequest::delete()
+            .uri(&format!("/courses/2/3/modules/{}", module.id))
+            .insert_header(auth)
+            .to_request();
+        test::call_service(&app, req).await;
+        let req = test::TestRequest::get().uri("/courses?q=container").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 0);
+    }
+
+    #[actix_rt::test]
+    async fn search_courses_no_match() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        let teacher_id: web::Path<i32> = web::Path::from(1);
-        let response = get_courses_for_teacher(app_state, teacher_id).await.unwrap();
+        let req = test::TestRequest::get().uri("/courses?q=%22unknown%22%20OR").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: CoursePage = test::read_body_json(resp).await;
+        assert_eq!(page.total, 0);
+        assert!(page.items.is_empty());
+        assert_eq!(page.next, None);
     }
 
     #[actix_rt::test]
     async fn get_one_course_success() {
-        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        let params: web::Path<(i32, i32)> = web::Path::from((1, 1));
-        let response = get_course_detail(app_state, params).await.unwrap();
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.id, 1);
+        assert_eq!(course.teacher_id, 1);
+        assert_eq!(course.name, "First course");
+        assert_eq!(course.level, Some(CourseLevel::Beginner));
     }
 
     #[actix_rt::test]
     async fn get_one_course_failure() {
-        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        let params: web::Path<(i32, i32)> = web::Path::from((1, 100));
-        let response = get_course_detail(app_state, params).await;
+        let req = test::TestRequest::get().uri("/courses/1/100").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        match response {
-            Ok(_) => println!("Something went wrong"),
-            Err(err) => assert_eq!(err.status_code(), StatusCode::NOT_FOUND),
-        }
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "course.not_found", "error_message": "Course Id not found" })
+        );
     }
 
     #[actix_rt::test]
     async fn update_course_success() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "name": "Course name changed",
+                "time": "2025-07-19T10:15:00",
+                "description": "This is another test course",
+                "language": "Chinese",
+                "level": "Intermediate",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
 
-        let params: web::Path<(i32, i32)> = web::Path::from((3, 4));
-        let update_param = web::Json(UpdateCourse {
-            name: Some("Course name changed".into()),
-            time: Some(NaiveDateTime::new(
+        assert_eq!(resp.status(), StatusCode::OK);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.id, 2);
+        assert_eq!(course.name, "Course name changed");
+        assert_eq!(course.description.as_deref(), Some("This is another test course"));
+        assert_eq!(course.language, Some(CourseLanguage::Chinese));
+        assert_eq!(
+            course.time,
+            Some(NaiveDateTime::new(
                 NaiveDate::from_ymd_opt(2025, 7, 19).expect("Unknown date"),
                 NaiveTime::from_hms_opt(10, 15, 0).expect("Unknown time"),
-            )),
-            description: Some("This is another test course".into()),
-            format: None,
-            structure: None,
-            duration: None,
-            price: None,
-            language: Some("Chinese".into()),
-            level: Some("Intermediate".into())
-        });
-        
-        let response = update_course_detail(app_state, update_param, params).await.unwrap();
+            ))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn update_course_replaces_all_fields() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Replaced course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.name, "Replaced course");
+        assert_eq!(course.price, None);
+        assert_eq!(course.time, None);
+        assert_eq!(course.description, None);
+    }
+
+    #[actix_rt::test]
+    async fn patch_course_keeps_missing_fields() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Only name changed" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.name, "Only name changed");
+        assert_eq!(course.price, Some(200));
+        assert_eq!(course.format, Some(CourseFormat::Live));
+        assert_eq!(course.level, Some(CourseLevel::Intermediate));
+        assert_eq!(
+            course.time,
+            Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2025, 7, 13).expect("Unknown date"),
+                NaiveTime::from_hms_opt(10, 15, 0).expect("Unknown time"),
+            ))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn patch_course_sets_explicit_null() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "price": null, "level": "Advanced" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get().uri("/courses/1/2").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.name, "Second course");
+        assert_eq!(course.price, None);
+        assert_eq!(course.level, Some(CourseLevel::Advanced));
+        assert_eq!(course.language, Some(CourseLanguage::English));
+    }
+
+    #[actix_rt::test]
+    async fn patch_course_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        let req = test::TestRequest::patch()
+            .uri("/courses/2/1")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Not my course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "course.not_found", "error_message": "Course Id not found" })
+        );
     }
 
-    #[ignore]
     #[actix_rt::test]
     async fn delete_course_success() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
 
-        let params: web::Path<(i32, i32)> = web::Path::from((1, 3));
-        let response = delete_course(app_state, params).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let body = test::read_body(resp).await;
+        assert!(body.is_empty());
 
-        assert_eq!(response.status(), StatusCode::OK);
+        let req = test::TestRequest::get().uri("/courses/1/2").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_rt::test]
     async fn delete_course_failure() {
         let app_state = create_app_state().await;
 
+        let req = test::TestRequest::delete().to_http_request();
         let params: web::Path<(i32, i32)> = web::Path::from((1, 101));
-        let response = delete_course(app_state, params).await;
+        let err = delete_course(req, seed_user(4), app_state, AuditContext::default(), params).await.unwrap_err();
+
+        assert!(matches!(err, MyError::NotFound { .. }));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(err.code(), "course.not_found");
+    }
+
+    #[actix_rt::test]
+    async fn post_new_course_forbidden() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 教师 1 不能以教师 2 的名义新建课程
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "teacher_id": 2, "name": "Not my course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "auth.forbidden", "error_message": "Not allowed to modify resources of another teacher" })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn update_and_delete_course_forbidden() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/2")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "name": "Hijacked" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/2")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get().uri("/courses/1/2").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.name, "Second course");
+    }
+
+    #[actix_rt::test]
+    async fn admin_can_modify_any_course() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/2/3")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "price": 180 }))
+            .to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.price, Some(180));
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/2/3")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
 
-        match response {
-            Ok(_) => println!("Something went wrong"),
-            Err(err) => assert_eq!(err.status_code(), StatusCode::NOT_FOUND),
-        }
+    #[actix_rt::test]
+    async fn student_cannot_modify_courses() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "teacher_id": 1, "name": "Student course" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // 学生仍然可以浏览课程
+        let req = test::TestRequest::get()
+            .uri("/courses/1/1")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
     }
-}
\ No newline at end of file
+
+    #[actix_rt::test]
+    async fn course_options_are_canonicalized() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({
+                "teacher_id": 1,
+                "name": "Aliased course",
+                "format": "video",
+                "language": "中文",
+                "level": " BEGINNER ",
+            }))
+            .to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.format, Some(CourseFormat::Video));
+        assert_eq!(course.language, Some(CourseLanguage::Chinese));
+        assert_eq!(course.level, Some(CourseLevel::Beginner));
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/courses/1/{}", course.id))
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "level": "高级", "format": null }))
+            .to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.level, Some(CourseLevel::Advanced));
+        assert_eq!(course.format, None);
+
+        // 过滤条件同样接受别名
+        let req = test::TestRequest::get().uri("/courses/1?level=beginner").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].name, "First course");
+    }
+
+    #[actix_rt::test]
+    async fn unknown_course_options_are_rejected() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "teacher_id": 1, "name": "Expert course", "level": "Expert" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "level", "message": "Unknown level: Expert" }],
+            })
+        );
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "name": "Klingon course", "language": "Klingon" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "language", "message": "Unknown language: Klingon" }],
+            })
+        );
+
+        let req = test::TestRequest::patch()
+            .uri("/courses/1/1")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "format": "Podcast" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({
+                "code": "validation.failed",
+                "error_message": "Validation failed",
+                "details": [{ "field": "format", "message": "Unknown format: Podcast" }],
+            })
+        );
+
+        let req = test::TestRequest::get().uri("/courses?format=Podcast").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn get_course_options_success() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get().uri("/meta/course-options").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let options: CourseOptions = test::read_body_json(resp).await;
+        let levels: Vec<&str> = options.levels.iter().map(|option| option.value.as_str()).collect();
+        assert_eq!(levels, ["Beginner", "Intermediate", "Advanced"]);
+        assert_eq!(options.levels[0].label, "初级");
+        assert_eq!(options.formats.len(), CourseFormat::ALL.len());
+        assert_eq!(options.languages.len(), CourseLanguage::ALL.len());
+    }
+}
@@ -0,0 +1,276 @@
+use actix_web::{http::header, web, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::state::AppState;
+
+/// 当前登录的学生选修课程，课程已满时进入候补名单
+pub async fn enroll_in_course(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    let student_id = user
+        .student_id
+        .ok_or_else(|| MyError::Forbidden("Only students can enroll in courses".into()))?;
+
+    app_state.db.enroll_student_db(&audit, teacher_id, course_id, student_id)
+        .await
+        .map(|enrollment| {
+            HttpResponse::Created()
+                .insert_header((
+                    header::LOCATION,
+                    format!("/courses/{}/{}/enrollments/{}", teacher_id, course_id, student_id),
+                ))
+                .json(enrollment)
+        })
+}
+
+/// 学生本人退课，或由课程所属教师、管理员将学生移出课程
+pub async fn withdraw_from_course(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, student_id) = params.into_inner();
+    if user.ensure_student(student_id).is_err() {
+        user.ensure_owner(teacher_id)?;
+    }
+
+    app_state.db.withdraw_student_db(&audit, teacher_id, course_id, student_id)
+        .await
+        .map(|_| HttpResponse::NoContent().finish())
+}
+
+pub async fn get_course_roster(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+
+    app_state.db.get_course_roster_db(teacher_id, course_id)
+        .await
+        .map(|roster| HttpResponse::Ok().json(roster))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::enrollment::{CourseRoster, Enrollment, EnrollmentStatus};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::{header, StatusCode};
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn enroll_in_course_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 6);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/2/enrollments")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/courses/1/2/enrollments/2");
+        let enrollment: Enrollment = test::read_body_json(resp).await;
+        assert_eq!(enrollment.course_id, 2);
+        assert_eq!(enrollment.student_id, 2);
+        assert_eq!(enrollment.status, EnrollmentStatus::Enrolled);
+    }
+
+    #[actix_rt::test]
+    async fn enroll_waitlist_and_promote() {
+        let app_state = create_app_state().await;
+        let student_one = bearer(&app_state, 5);
+        let student_two = bearer(&app_state, 6);
+        let teacher_two = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 课程 3 的容量为 1，第二个学生进入候补名单
+        let req = test::TestRequest::post()
+            .uri("/courses/2/3/enrollments")
+            .insert_header(student_one.clone())
+            .to_request();
+        let enrollment: Enrollment = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(enrollment.status, EnrollmentStatus::Enrolled);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/2/3/enrollments")
+            .insert_header(student_two)
+            .to_request();
+        let enrollment: Enrollment = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(enrollment.status, EnrollmentStatus::Waitlisted);
+
+        let req = test::TestRequest::get()
+            .uri("/courses/2/3/enrollments")
+            .insert_header(teacher_two.clone())
+            .to_request();
+        let roster: CourseRoster = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roster.capacity, Some(1));
+        assert_eq!(roster.enrolled.len(), 1);
+        assert_eq!(roster.waitlist[0].student_id, 2);
+
+        // 第一个学生退课后，候补的学生自动递补
+        let req = test::TestRequest::delete()
+            .uri("/courses/2/3/enrollments/1")
+            .insert_header(student_one)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get()
+            .uri("/courses/2/3/enrollments")
+            .insert_header(teacher_two)
+            .to_request();
+        let roster: CourseRoster = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roster.enrolled.len(), 1);
+        assert_eq!(roster.enrolled[0].student_id, 2);
+        assert!(roster.waitlist.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn raise_capacity_promotes_waitlist() {
+        let app_state = create_app_state().await;
+        let student_one = bearer(&app_state, 5);
+        let student_two = bearer(&app_state, 6);
+        let teacher_two = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        for student in [student_one, student_two] {
+            let req = test::TestRequest::post()
+                .uri("/courses/2/3/enrollments")
+                .insert_header(student)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CREATED);
+        }
+
+        // 课程 3 的容量从 1 调到 2，候补的学生自动递补
+        let req = test::TestRequest::patch()
+            .uri("/courses/2/3")
+            .insert_header(teacher_two.clone())
+            .set_json(serde_json::json!({ "capacity": 2 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri("/courses/2/3/enrollments")
+            .insert_header(teacher_two)
+            .to_request();
+        let roster: CourseRoster = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(roster.capacity, Some(2));
+        assert_eq!(roster.enrolled.len(), 2);
+        assert!(roster.waitlist.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn enroll_in_course_failure() {
+        let app_state = create_app_state().await;
+        let student_one = bearer(&app_state, 5);
+        let teacher_one = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/enrollments")
+            .insert_header(student_one.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "resource.conflict", "error_message": "Student is already enrolled in this course" })
+        );
+
+        // 课程 3 不属于教师 1
+        let req = test::TestRequest::post()
+            .uri("/courses/1/3/enrollments")
+            .insert_header(student_one)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/2/enrollments")
+            .insert_header(teacher_one)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn get_course_roster_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/courses/1/1/enrollments")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let roster: CourseRoster = test::read_body_json(resp).await;
+        assert_eq!(roster.course_id, 1);
+        assert_eq!(roster.capacity, None);
+        assert_eq!(roster.enrolled.len(), 1);
+        assert_eq!(roster.enrolled[0].name, "Student One");
+        assert!(roster.waitlist.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn get_course_roster_forbidden() {
+        let app_state = create_app_state().await;
+        let teacher_two = bearer(&app_state, 2);
+        let student_one = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        for auth in [teacher_two, student_one] {
+            let req = test::TestRequest::get()
+                .uri("/courses/1/1/enrollments")
+                .insert_header(auth)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn withdraw_from_course_by_owner() {
+        let app_state = create_app_state().await;
+        let student_two = bearer(&app_state, 6);
+        let teacher_one = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 学生不能替其他学生退课
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/enrollments/1")
+            .insert_header(student_two.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/enrollments/2")
+            .insert_header(student_two)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // 课程所属教师可以把学生移出课程
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/enrollments/1")
+            .insert_header(teacher_one)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+}
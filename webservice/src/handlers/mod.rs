@@ -1,3 +1,13 @@
+pub mod admin;
+pub mod audit;
+pub mod auth;
+pub mod batch;
+pub mod catalog;
+pub mod content;
 pub mod course;
+pub mod enrollment;
 pub mod general;
-pub mod teacher;
\ No newline at end of file
+pub mod review;
+pub mod student;
+pub mod teacher;
+pub mod trash;
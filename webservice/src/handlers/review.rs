@@ -0,0 +1,325 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::handlers::course::next_page_link;
+use crate::models::audit::AuditContext;
+use crate::models::review::{CreateReview, ReviewPage, ReviewQuery, UpdateReview};
+use crate::state::AppState;
+
+/// 当前登录的学生评价已选修的课程
+pub async fn post_new_review(
+    user: AuthenticatedUser,
+    new_review: web::Json<CreateReview>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    let student_id = user
+        .student_id
+        .ok_or_else(|| MyError::Forbidden("Only students can review courses".into()))?;
+
+    app_state.db.post_new_review_db(&audit, teacher_id, course_id, student_id, new_review.try_into()?)
+        .await
+        .map(|review| {
+            HttpResponse::Created()
+                .insert_header((
+                    header::LOCATION,
+                    format!("/courses/{}/{}/reviews/{}", teacher_id, course_id, review.id),
+                ))
+                .json(review)
+        })
+}
+
+pub async fn get_reviews_for_course(
+    req: HttpRequest,
+    app_state: web::Data<AppState>,
+    params: web::Path<(i32, i32)>,
+    query: web::Query<ReviewQuery>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    let query = query.into_inner();
+    query.validate()?;
+
+    let (limit, offset) = (query.limit(), query.offset());
+    app_state.db.get_reviews_for_course_db(teacher_id, course_id, &query)
+        .await
+        .map(|(reviews, total)| {
+            HttpResponse::Ok().json(ReviewPage {
+                total,
+                limit,
+                offset,
+                next: next_page_link(&req, limit, offset, reviews.len(), total),
+                items: reviews,
+            })
+        })
+}
+
+pub async fn get_review_detail(
+    app_state: web::Data<AppState>,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, review_id) = params.into_inner();
+    app_state.db.get_review_db(teacher_id, course_id, review_id)
+        .await
+        .map(|review| HttpResponse::Ok().json(review))
+}
+
+/// 只有评价的作者可以修改评价
+pub async fn update_review(
+    user: AuthenticatedUser,
+    update_review: web::Json<UpdateReview>,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, review_id) = params.into_inner();
+    let update_review = update_review.try_into()?;
+    let review = app_state.db.get_review_db(teacher_id, course_id, review_id).await?;
+    if user.student_id != Some(review.student_id) {
+        return Err(MyError::Forbidden("Not allowed to modify another student's review".into()));
+    }
+
+    app_state.db.update_review_db(&audit, teacher_id, course_id, review_id, update_review)
+        .await
+        .map(|review| HttpResponse::Ok().json(review))
+}
+
+/// 评价的作者或管理员可以删除评价
+pub async fn delete_review(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id, review_id) = params.into_inner();
+    let review = app_state.db.get_review_db(teacher_id, course_id, review_id).await?;
+    user.ensure_student(review.student_id)?;
+
+    app_state.db.delete_review_db(&audit, teacher_id, course_id, review_id)
+        .await
+        .map(|_| HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::course::Course;
+    use crate::models::review::{Review, ReviewPage};
+    use crate::models::teacher::TeacherDetail;
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::{header, StatusCode};
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn get_reviews_for_course_success() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/1/reviews").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: ReviewPage = test::read_body_json(resp).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.next, None);
+        assert_eq!(page.items[0].student_name, "Student One");
+        assert_eq!(page.items[0].rating, 4);
+        assert_eq!(page.items[0].comment.as_deref(), Some("Clear and well paced"));
+    }
+
+    #[actix_rt::test]
+    async fn get_reviews_for_course_failure() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/courses/1/3/reviews").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get().uri("/courses/1/1/reviews?limit=0").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn post_new_review_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 6);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/enrollments")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/reviews")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "rating": 2 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/courses/1/1/reviews/2");
+        let review: Review = test::read_body_json(resp).await;
+        assert_eq!(review.student_id, 2);
+        assert_eq!(review.rating, 2);
+        assert_eq!(review.comment, None);
+
+        // 课程详情中的评价汇总随之更新
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.avg_rating, Some(3.0));
+        assert_eq!(course.review_count, 2);
+
+        // 最新的评价排在最前面
+        let req = test::TestRequest::get().uri("/courses/1/1/reviews?limit=1").to_request();
+        let page: ReviewPage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].id, 2);
+        assert_eq!(page.next.as_deref(), Some("/courses/1/1/reviews?limit=1&offset=1"));
+    }
+
+    #[actix_rt::test]
+    async fn post_new_review_failure() {
+        let app_state = create_app_state().await;
+        let student_one = bearer(&app_state, 5);
+        let student_two = bearer(&app_state, 6);
+        let teacher_one = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let cases = [
+            (student_one.clone(), "/courses/1/1/reviews", 5, StatusCode::CONFLICT),
+            (student_one.clone(), "/courses/1/2/reviews", 5, StatusCode::FORBIDDEN),
+            (student_one, "/courses/1/1/reviews", 6, StatusCode::BAD_REQUEST),
+            (student_two, "/courses/1/3/reviews", 5, StatusCode::NOT_FOUND),
+            (teacher_one, "/courses/1/1/reviews", 5, StatusCode::FORBIDDEN),
+        ];
+        for (auth, uri, rating, status) in cases {
+            let req = test::TestRequest::post()
+                .uri(uri)
+                .insert_header(auth)
+                .set_json(serde_json::json!({ "rating": rating }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), status, "{} with rating {}", uri, rating);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn update_review_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "rating": 5 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let review: Review = test::read_body_json(resp).await;
+        assert_eq!(review.rating, 5);
+        assert_eq!(review.comment.as_deref(), Some("Clear and well paced"));
+
+        // 显式传 null 清空评语，缺省的评分保持不变
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "comment": null }))
+            .to_request();
+        let review: Review = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(review.rating, 5);
+        assert_eq!(review.comment, None);
+    }
+
+    #[actix_rt::test]
+    async fn update_review_failure() {
+        let app_state = create_app_state().await;
+        let student_one = bearer(&app_state, 5);
+        let student_two = bearer(&app_state, 6);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(student_two)
+            .set_json(serde_json::json!({ "rating": 1 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(student_one.clone())
+            .set_json(serde_json::json!({ "rating": 0 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_error(resp).await;
+        assert_eq!(body["code"], "validation.failed");
+        assert_eq!(
+            body["details"],
+            serde_json::json!([{ "field": "rating", "message": "must be between 1 and 5" }])
+        );
+
+        let req = test::TestRequest::put()
+            .uri("/courses/1/2/reviews/1")
+            .insert_header(student_one)
+            .set_json(serde_json::json!({ "rating": 3 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn delete_review_success() {
+        let app_state = create_app_state().await;
+        let student_two = bearer(&app_state, 6);
+        let admin = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(student_two)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1/reviews/1")
+            .insert_header(admin)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/courses/1/1/reviews/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let course: Course = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(course.avg_rating, None);
+        assert_eq!(course.review_count, 0);
+    }
+
+    #[actix_rt::test]
+    async fn teacher_detail_includes_rating() {
+        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/teachers/1").to_request();
+        let teacher: TeacherDetail = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(teacher.teacher.name, "Teacher One");
+        assert_eq!(teacher.rating.avg_rating, Some(4.0));
+        assert_eq!(teacher.rating.review_count, 1);
+
+        let req = test::TestRequest::get().uri("/teachers/3").to_request();
+        let teacher: TeacherDetail = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(teacher.rating.avg_rating, None);
+        assert_eq!(teacher.rating.review_count, 0);
+    }
+}
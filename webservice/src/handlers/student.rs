@@ -0,0 +1,91 @@
+use actix_web::{web, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::state::AppState;
+
+pub async fn get_student_detail(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    params: web::Path<i32>,
+) -> Result<HttpResponse, MyError> {
+    let student_id = params.into_inner();
+    user.ensure_student(student_id)?;
+    app_state.db.get_student_details_db(student_id)
+        .await
+        .map(|student| HttpResponse::Ok().json(student))
+}
+
+pub async fn get_student_courses(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    params: web::Path<i32>,
+) -> Result<HttpResponse, MyError> {
+    let student_id = params.into_inner();
+    user.ensure_student(student_id)?;
+    app_state.db.get_student_courses_db(student_id)
+        .await
+        .map(|courses| HttpResponse::Ok().json(courses))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::enrollment::{EnrollmentStatus, StudentCourse};
+    use crate::models::student::Student;
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+
+    #[actix_rt::test]
+    async fn get_student_detail_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/students/1").insert_header(auth).to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let student: Student = test::read_body_json(resp).await;
+        assert_eq!(student.name, "Student One");
+        assert_eq!(student.email, "one@example.com");
+    }
+
+    #[actix_rt::test]
+    async fn get_student_detail_failure() {
+        let app_state = create_app_state().await;
+        let student_two = bearer(&app_state, 6);
+        let admin = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/students/1").insert_header(student_two).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get().uri("/students/100").insert_header(admin).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "student.not_found", "error_message": "Student Id not found" })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn get_student_courses_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/students/1/courses")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let courses: Vec<StudentCourse> = test::read_body_json(resp).await;
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].course.name, "First course");
+        assert_eq!(courses[0].status, EnrollmentStatus::Enrolled);
+    }
+}
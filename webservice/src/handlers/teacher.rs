@@ -1,164 +1,372 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::auth::AuthenticatedUser;
 use crate::errors::MyError;
 use crate::state::AppState;
-use crate::dbaccess::teacher::*;
-use crate::models::teacher::{CreateTeacher, UpdateTeacher};
+use crate::models::audit::AuditContext;
+use crate::models::teacher::{CreateTeacher, DeleteTeacherQuery, Teacher, TeacherDetail, UpdateTeacher};
+use crate::precondition::{check_if_match, conditional_get, etag, has_if_match};
 
 pub async fn post_new_teacher(
     new_teacher: web::Json<CreateTeacher>,
     app_state: web::Data<AppState>,
+    audit: AuditContext,
 ) -> Result<HttpResponse, MyError> {
-    post_new_teacher_db(&app_state.db, new_teacher.into())
-    .await
-    .map(|_| HttpResponse::Ok().json("Post new teacher successfully."))
+    app_state.db.post_new_teacher_db(&audit, new_teacher.try_into()?)
+        .await
+        .map(|teacher| {
+            HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/teachers/{}", teacher.id)))
+                .json(teacher)
+        })
 }
 
 pub async fn get_all_teachers(app_state: web::Data<AppState>) -> Result<HttpResponse, MyError> {
-    get_all_teachers_db(&app_state.db)
+    app_state.db.get_all_teachers_db()
         .await
         .map(|teachers| HttpResponse::Ok().json(teachers))
 }
 
+/// 教师详情，GET 和 If-Match 比较使用同一个表示
+async fn teacher_detail(app_state: &AppState, teacher_id: i32) -> Result<TeacherDetail, MyError> {
+    let teacher = app_state.db.get_teacher_details_db(teacher_id).await?;
+    let rating = app_state.db.get_teacher_rating_db(teacher_id).await?;
+    Ok(TeacherDetail { teacher, rating })
+}
+
 pub async fn get_teacher_detail(
+    req: HttpRequest,
     app_state: web::Data<AppState>,
     params: web::Path<i32>
 ) -> Result<HttpResponse, MyError> {
     let teacher_id = params.into_inner();
-    get_teacher_details_db(&app_state.db, teacher_id)
+    teacher_detail(&app_state, teacher_id)
         .await
-        .map(|teacher| HttpResponse::Ok().json(teacher))
+        .map(|detail| conditional_get(&req, detail.teacher.version, &detail))
+}
+
+/// 请求带有 If-Match 时与教师详情当前的 ETag 比较，返回写入时需要再次核对的版本号
+async fn if_match_teacher(
+    req: &HttpRequest,
+    app_state: &AppState,
+    teacher_id: i32,
+) -> Result<Option<i32>, MyError> {
+    if !has_if_match(req) {
+        return Ok(None);
+    }
+    let detail = teacher_detail(app_state, teacher_id).await?;
+    check_if_match(req, &etag(detail.teacher.version, &detail))?;
+    Ok(Some(detail.teacher.version))
+}
+
+/// 修改后的教师，附带与 GET 教师详情一致的新 ETag，客户端可以直接用它发起下一次条件修改
+async fn teacher_response(app_state: &AppState, teacher: Teacher) -> Result<HttpResponse, MyError> {
+    let rating = app_state.db.get_teacher_rating_db(teacher.id).await?;
+    let detail = TeacherDetail { teacher, rating };
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ETag(etag(detail.teacher.version, &detail)))
+        .json(detail.teacher))
 }
 
 pub async fn update_teacher_detail(
+    req: HttpRequest,
+    user: AuthenticatedUser,
     app_state: web::Data<AppState>,
+    audit: AuditContext,
     update_teacher: web::Json<UpdateTeacher>,
     params: web::Path<i32>
 ) -> Result<HttpResponse, MyError> {
     let teacher_id = params.into_inner();
-    update_teacher_details_db(&app_state.db, teacher_id, update_teacher.into())
-        .await
-        .map(|msg| HttpResponse::Ok().json(msg))
+    user.ensure_owner(teacher_id)?;
+    let update_teacher = update_teacher.try_into()?;
+    let expected_version = if_match_teacher(&req, &app_state, teacher_id).await?;
+    let teacher = app_state.db.update_teacher_details_db(&audit, teacher_id, update_teacher, expected_version).await?;
+    teacher_response(&app_state, teacher).await
 }
 
 pub async fn delete_teacher(
+    req: HttpRequest,
+    user: AuthenticatedUser,
     app_state: web::Data<AppState>,
-    params: web::Path<i32>
+    audit: AuditContext,
+    params: web::Path<i32>,
+    query: web::Query<DeleteTeacherQuery>,
 ) -> Result<HttpResponse, MyError> {
     let teacher_id = params.into_inner();
-    delete_teacher_db(&app_state.db, teacher_id)
+    user.ensure_owner(teacher_id)?;
+    let policy = query.policy(teacher_id)?;
+    let expected_version = if_match_teacher(&req, &app_state, teacher_id).await?;
+    app_state.db.delete_teacher_db(&audit, teacher_id, policy, expected_version)
         .await
-        .map(|msg| HttpResponse::Ok().json(msg))
+        .map(|_| HttpResponse::NoContent().finish())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::env;
-    use std::sync::Mutex;
+    use crate::errors::{MyErrorResponse, REQUEST_ID_HEADER};
+    use crate::models::course::CoursePage;
+    use crate::models::teacher::DeletePolicy;
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error, seed_user};
     use actix_web::http::StatusCode;
-    use actix_web::ResponseError;
-    use dotenv::dotenv;
-    use sqlx::mysql::MySqlPoolOptions;
-    use sqlx::MySqlPool;
-
-    async fn create_db_pool() -> MySqlPool {
-        // 检测并读取 .env 文件中的内容，若不存在也会跳过异常
-        dotenv().ok();
-
-        let db_url = env::var("DATABASE_URL")
-            .expect("DATABASE_URL 没有在 .env 文件里设置");
-
-        // 创建数据库连接池
-        MySqlPoolOptions::new()
-            .connect(&db_url)
-            .await
-            .unwrap()
-    }
+    use actix_web::{test, ResponseError};
 
-    async fn create_app_state() -> web::Data<AppState> {
-        let db_pool = create_db_pool().await;
-
-        web::Data::new(AppState {
-            health_check_response: "".to_string(),
-            visit_count: Mutex::new(0),
-            db: db_pool,
-        })
+    #[actix_rt::test]
+    async fn post_new_teacher_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 模拟管理员添加教师的请求
+        let req = test::TestRequest::post()
+            .uri("/teachers/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "name": "A New Teacher",
+                "picture_url": "https://example.com/new.jpg",
+                "profile": "This is a test profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/teachers/4");
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.id, 4);
+        assert_eq!(teacher.name, "A New Teacher");
+        assert_eq!(teacher.profile, "This is a test profile");
     }
 
-    #[ignore]
     #[actix_rt::test]
-    async fn post_new_teacher_success() {
+    async fn post_new_teacher_invalid_fields() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/teachers/")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "name": "",
+                "picture_url": "not a url",
+                "profile": "This is a test profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["details"],
+            serde_json::json!([
+                { "field": "name", "message": "must not be empty" },
+                { "field": "picture_url", "message": "must be a valid http or https URL" },
+            ])
+        );
+    }
 
-        let teacher = web::Json(CreateTeacher {
-            name: "A New Teacher".into(),
-            picture_url: "https://i2.hdslb.com/bfs/article/1f8a3ece569b3d61903fe2062cf71d96435d6f8b.jpg@1192w.avif".into(),
-            profile: "This is a test profile".into(),
-        });
+    #[actix_rt::test]
+    async fn get_all_teachers_success() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        // 模拟添加教师的请求
-        let response = post_new_teacher(teacher, app_state).await.unwrap();
+        let req = test::TestRequest::get().uri("/teachers/").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let teachers: Vec<Teacher> = test::read_body_json(resp).await;
+        let names: Vec<&str> = teachers.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Teacher One", "Teacher Two", "Teacher Three"]);
     }
 
     #[actix_rt::test]
-    async fn get_all_teachers_success() {
+    async fn get_all_teachers_empty() {
         let app_state = create_app_state().await;
+        for teacher_id in 1..=3 {
+            app_state.db.delete_teacher_db(&AuditContext::default(), teacher_id, DeletePolicy::Cascade, None).await.unwrap();
+        }
+        let app = test::init_service(create_app(app_state)).await;
 
-        let response = get_all_teachers(app_state).await.unwrap();
+        let req = test::TestRequest::get().uri("/teachers/").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let teachers: Vec<Teacher> = test::read_body_json(resp).await;
+        assert!(teachers.is_empty());
     }
 
     #[actix_rt::test]
     async fn get_teacher_detail_success() {
-        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        let params: web::Path<i32> = web::Path::from(1);
-        let response = get_teacher_detail(app_state, params).await.unwrap();
+        let req = test::TestRequest::get().uri("/teachers/1").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.id, 1);
+        assert_eq!(teacher.name, "Teacher One");
+        assert_eq!(teacher.picture_url, "https://example.com/one.jpg");
     }
 
     #[actix_rt::test]
     async fn get_teacher_detail_failure() {
-        let app_state = create_app_state().await;
+        let app = test::init_service(create_app(create_app_state().await)).await;
 
-        let params: web::Path<i32> = web::Path::from(100);
-        let response = get_teacher_detail(app_state, params).await;
+        let req = test::TestRequest::get().uri("/teachers/100").to_request();
+        let resp = test::call_service(&app, req).await;
 
-        match response {
-            Ok(_) => println!("Something went wrong"),
-            Err(err) => assert_eq!(err.status_code(), StatusCode::NOT_FOUND),
-        }
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "teacher.not_found", "error_message": "Teacher Id not found" })
+        );
     }
 
     #[actix_rt::test]
-    async fn update_teacher_success() {
-        let app_state = create_app_state().await;
+    async fn error_response_carries_request_id() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        // 沿用客户端传入的请求 ID
+        let req = test::TestRequest::get()
+            .uri("/teachers/100")
+            .insert_header((REQUEST_ID_HEADER, "client-id-1"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get(REQUEST_ID_HEADER).unwrap(), "client-id-1");
+        let body: MyErrorResponse = test::read_body_json(resp).await;
+        assert_eq!(body.code, "teacher.not_found");
+        assert_eq!(body.request_id.as_deref(), Some("client-id-1"));
+
+        // 不合法的请求 ID 会被替换成新生成的
+        let req = test::TestRequest::get()
+            .uri("/teachers/100")
+            .insert_header((REQUEST_ID_HEADER, "bad id!"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let header_id = resp.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert_ne!(header_id, "bad id!");
+        let body: MyErrorResponse = test::read_body_json(resp).await;
+        assert_eq!(body.request_id, Some(header_id));
+    }
 
-        let params: web::Path<i32> = web::Path::from(2);
-        let update_param = web::Json(UpdateTeacher {
-            name: Some("Teacher name changed".into()),
-            picture_url: Some("https://i2.hdslb.com/bfs/article/6e82414fa0c96d530caa120caed421240df75cfc.jpg@1192w.avif".into()),
-            profile: Some("This is a update profile".into()),
-        });
-        let response = update_teacher_detail(app_state, update_param, params).await.unwrap();
+    #[actix_rt::test]
+    async fn error_response_keeps_cors_headers() {
+        let app = test::init_service(create_app(create_app_state().await)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/teachers/100")
+            .insert_header((header::ORIGIN, "http://localhost:8082"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "http://localhost:8082"
+        );
+        assert!(resp.headers().contains_key(REQUEST_ID_HEADER));
+        assert_eq!(read_error(resp).await["code"], "teacher.not_found");
+    }
 
-        assert_eq!(response.status(), StatusCode::OK);
+    #[actix_rt::test]
+    async fn update_teacher_success() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({
+                "name": "Teacher name changed",
+                "profile": "This is a update profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.id, 2);
+        assert_eq!(teacher.name, "Teacher name changed");
+        assert_eq!(teacher.profile, "This is a update profile");
+        assert_eq!(teacher.picture_url, "https://example.com/two.jpg");
+    }
 
+    #[actix_rt::test]
+    async fn update_teacher_if_match() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 2);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::get().uri("/teachers/2").to_request();
+        let resp = test::call_service(&app, req).await;
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+
+        let req = test::TestRequest::get()
+            .uri("/teachers/2")
+            .insert_header((header::IF_NONE_MATCH, etag.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/2")
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, etag.clone()))
+            .set_json(serde_json::json!({ "name": "Teacher name changed" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let new_etag = resp.headers().get(header::ETAG).unwrap().clone();
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.version, 2);
+
+        // 修改之后旧的 ETag 不再匹配，响应中的新 ETag 与 GET 返回的一致
+        let req = test::TestRequest::get()
+            .uri("/teachers/2")
+            .insert_header((header::IF_NONE_MATCH, etag.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::ETAG), Some(&new_etag));
+
+        // 可以直接用新 ETag 再次修改
+        let req = test::TestRequest::put()
+            .uri("/teachers/2")
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, new_etag))
+            .set_json(serde_json::json!({ "profile": "Second conditional update" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/2")
+            .insert_header(auth)
+            .insert_header((header::IF_MATCH, etag))
+            .set_json(serde_json::json!({ "name": "Lost update" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(read_error(resp).await["code"], "precondition.failed");
     }
 
-    #[ignore]
     #[actix_rt::test]
     async fn delete_teacher_success() {
         let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/teachers/3")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
 
-        let params: web::Path<i32> = web::Path::from(3);
-        let response = delete_teacher(app_state, params).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 
-        assert_eq!(response.status(), StatusCode::OK);
+        let req = test::TestRequest::get().uri("/teachers/3").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_rt::test]
@@ -166,11 +374,196 @@ mod tests {
         let app_state = create_app_state().await;
 
         let params: web::Path<i32> = web::Path::from(100);
-        let response = delete_teacher(app_state, params).await;
+        let query = web::Query(DeleteTeacherQuery::default());
+        let req = test::TestRequest::delete().to_http_request();
+        let err = delete_teacher(req, seed_user(4), app_state, AuditContext::default(), params, query).await.unwrap_err();
+
+        assert!(matches!(err, MyError::NotFound { .. }));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(err.code(), "teacher.not_found");
+    }
+
+    #[actix_rt::test]
+    async fn update_teacher_failure() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/100")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Nobody" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(read_error(resp).await["code"], "teacher.not_found");
+    }
+
+    #[actix_rt::test]
+    async fn delete_teacher_with_courses_is_rejected() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/teachers/1")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body = read_error(resp).await;
+        assert_eq!(body["code"], "resource.conflict");
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 2);
+    }
+
+    #[actix_rt::test]
+    async fn delete_teacher_cascade() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let student_auth = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/teachers/1?on_courses=cascade")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 0);
+
+        // 课程的选课记录也随之删除
+        let req = test::TestRequest::get()
+            .uri("/students/1/courses")
+            .insert_header(student_auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let courses: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(courses.is_empty());
+    }
 
-        match response {
-            Ok(_) => println!("Something went wrong"),
-            Err(err) => assert_eq!(err.status_code(), StatusCode::NOT_FOUND),
+    #[actix_rt::test]
+    async fn delete_teacher_reassign() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/teachers/1?on_courses=reassign&reassign_to=2")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/courses/2").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 3);
+        assert!(page.items.iter().all(|course| course.teacher_id == 2));
+    }
+
+    #[actix_rt::test]
+    async fn delete_teacher_invalid_policy() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let cases = [
+            ("on_courses=archive", "on_courses", "must be one of reject, cascade, reassign"),
+            ("on_courses=reassign", "reassign_to", "is required when on_courses is reassign"),
+            ("on_courses=reassign&reassign_to=1", "reassign_to", "must be a different teacher"),
+            ("on_courses=reassign&reassign_to=99", "reassign_to", "Teacher does not exist"),
+        ];
+        for (query, field, message) in cases {
+            let req = test::TestRequest::delete()
+                .uri(&format!("/teachers/1?{}", query))
+                .insert_header(auth.clone())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{}", query);
+            let body = read_error(resp).await;
+            assert_eq!(body["details"], serde_json::json!([{ "field": field, "message": message }]));
         }
+
+        // 失败的请求不会删除教师
+        let req = test::TestRequest::get().uri("/teachers/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn update_teacher_forbidden() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/2")
+            .insert_header(auth)
+            .set_json(serde_json::json!({ "name": "Hijacked" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
     }
-}
\ No newline at end of file
+
+    #[actix_rt::test]
+    async fn admin_can_modify_any_teacher() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/teachers/1")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "profile": "Edited by admin" }))
+            .to_request();
+        let teacher: Teacher = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(teacher.profile, "Edited by admin");
+
+        let req = test::TestRequest::delete()
+            .uri("/teachers/3")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[actix_rt::test]
+    async fn teacher_cannot_create_or_delete_teachers() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 3);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/teachers/")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({
+                "name": "A New Teacher",
+                "picture_url": "https://example.com/new.jpg",
+                "profile": "This is a test profile",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // 即使是自己的资料，删除教师也只能由管理员执行
+        let req = test::TestRequest::delete()
+            .uri("/teachers/3")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            read_error(resp).await,
+            serde_json::json!({ "code": "auth.forbidden", "error_message": "Insufficient role for this operation" })
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use actix_web::{web, HttpResponse};
+use crate::auth::AuthenticatedUser;
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::state::AppState;
+
+/// 管理员可以看到全部已删除的教师和课程，教师只能看到自己已删除的课程
+pub async fn get_trash(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, MyError> {
+    let teacher_id = if user.is_admin() {
+        None
+    } else {
+        let teacher_id = user
+            .teacher_id
+            .ok_or_else(|| MyError::Forbidden("Insufficient role for this operation".into()))?;
+        Some(teacher_id)
+    };
+    app_state.db.get_trash_db(teacher_id)
+        .await
+        .map(|trash| HttpResponse::Ok().json(trash))
+}
+
+pub async fn restore_course(
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, MyError> {
+    let (teacher_id, course_id) = params.into_inner();
+    user.ensure_owner(teacher_id)?;
+    app_state.db.restore_course_db(&audit, teacher_id, course_id)
+        .await
+        .map(|course| HttpResponse::Ok().json(course))
+}
+
+pub async fn restore_teacher(
+    app_state: web::Data<AppState>,
+    audit: AuditContext,
+    params: web::Path<i32>,
+) -> Result<HttpResponse, MyError> {
+    let teacher_id = params.into_inner();
+    app_state.db.restore_teacher_db(&audit, teacher_id)
+        .await
+        .map(|teacher| HttpResponse::Ok().json(teacher))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbaccess::db_now;
+    use crate::models::audit::AuditContext;
+    use crate::models::course::{Course, CoursePage};
+    use crate::models::teacher::{DeletePolicy, Teacher};
+    use crate::models::trash::{PurgeSummary, Trash};
+    use crate::test_utils::{bearer, create_app, create_app_state, read_error};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use chrono::Duration;
+
+    #[actix_rt::test]
+    async fn delete_and_restore_course() {
+        let app_state = create_app_state().await;
+        let auth = bearer(&app_state, 1);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/1")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        // 已删除的课程不再出现在任何查询中
+        let req = test::TestRequest::get().uri("/courses/1/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let req = test::TestRequest::get().uri("/courses?q=First").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.total, 0);
+        let req = test::TestRequest::get().uri("/courses/1/1/modules").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri("/trash")
+            .insert_header(auth.clone())
+            .to_request();
+        let trash: Trash = test::call_and_read_body_json(&app, req).await;
+        assert!(trash.teachers.is_empty());
+        assert_eq!(trash.courses.len(), 1);
+        assert_eq!(trash.courses[0].id, 1);
+
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/restore")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let course: Course = test::read_body_json(resp).await;
+        assert_eq!(course.name, "First course");
+        assert_eq!(course.duration, 90);
+        assert_eq!(course.review_count, 1);
+
+        // 已恢复的课程不能再次恢复
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/restore")
+            .insert_header(auth)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(read_error(resp).await["code"], "course.not_found");
+    }
+
+    #[actix_rt::test]
+    async fn trash_is_scoped_to_owner() {
+        let app_state = create_app_state().await;
+        let teacher_one = bearer(&app_state, 1);
+        let teacher_two = bearer(&app_state, 2);
+        let admin = bearer(&app_state, 4);
+        let student = bearer(&app_state, 5);
+        let app = test::init_service(create_app(app_state)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/2")
+            .insert_header(teacher_one)
+            .to_request();
+        test::call_service(&app, req).await;
+        let req = test::TestRequest::delete()
+            .uri("/teachers/3")
+            .insert_header(admin.clone())
+            .to_request();
+        test::call_service(&app, req).await;
+
+        let req = test::TestRequest::get()
+            .uri("/trash")
+            .insert_header(teacher_two.clone())
+            .to_request();
+        let trash: Trash = test::call_and_read_body_json(&app, req).await;
+        assert!(trash.teachers.is_empty());
+        assert!(trash.courses.is_empty());
+
+        let req = test::TestRequest::get().uri("/trash").insert_header(admin).to_request();
+        let trash: Trash = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(trash.teachers.iter().map(|t| t.id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(trash.courses.iter().map(|c| c.id).collect::<Vec<_>>(), vec![2]);
+
+        // 只有课程所属教师或管理员可以恢复，学生不能查看回收站
+        let req = test::TestRequest::post()
+            .uri("/courses/1/2/restore")
+            .insert_header(teacher_two)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let req = test::TestRequest::get().uri("/trash").insert_header(student).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn restore_teacher_restores_cascaded_courses() {
+        let app_state = create_app_state().await;
+        let admin = bearer(&app_state, 4);
+        let app = test::init_service(create_app(app_state)).await;
+
+        // 课程 2 单独删除，之后教师 1 连同课程 1 一起删除
+        let req = test::TestRequest::delete()
+            .uri("/courses/1/2")
+            .insert_header(admin.clone())
+            .to_request();
+        test::call_service(&app, req).await;
+        // deleted_at 精确到秒，等一秒让两次删除的时间不同
+        actix_rt::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let req = test::TestRequest::delete()
+            .uri("/teachers/1?on_courses=cascade")
+            .insert_header(admin.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get().uri("/teachers/1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // 教师仍在回收站中时不能单独恢复课程
+        let req = test::TestRequest::post()
+            .uri("/courses/1/1/restore")
+            .insert_header(admin.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::post()
+            .uri("/teachers/1/restore")
+            .insert_header(admin.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let teacher: Teacher = test::read_body_json(resp).await;
+        assert_eq!(teacher.name, "Teacher One");
+
+        let req = test::TestRequest::get().uri("/courses/1").to_request();
+        let page: CoursePage = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page.items.iter().map(|c| c.id).collect::<Vec<_>>(), vec![1]);
+
+        let req = test::TestRequest::get().uri("/trash").insert_header(admin).to_request();
+        let trash: Trash = test::call_and_read_body_json(&app, req).await;
+        assert!(trash.teachers.is_empty());
+        assert_eq!(trash.courses.iter().map(|c| c.id).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[actix_rt::test]
+    async fn purge_deletes_expired_rows() {
+        let app_state = create_app_state().await;
+        app_state.db.delete_course_db(&AuditContext::default(), 2, 3, None).await.unwrap();
+        app_state.db.delete_teacher_db(&AuditContext::default(), 3, DeletePolicy::Reject, None).await.unwrap();
+
+        // 还没到保留期时不删除任何数据
+        let summary = app_state
+            .db
+            .purge_deleted_db(&AuditContext::default(), db_now() - Duration::days(30))
+            .await
+            .unwrap();
+        assert_eq!(summary, PurgeSummary::default());
+
+        let summary = app_state
+            .db
+            .purge_deleted_db(&AuditContext::default(), db_now() + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(summary, PurgeSummary { teachers: 1, courses: 1 });
+
+        let trash = app_state.db.get_trash_db(None).await.unwrap();
+        assert!(trash.teachers.is_empty());
+        assert!(trash.courses.is_empty());
+
+        // 物理删除后无法再恢复
+        let err = app_state.db.restore_teacher_db(&AuditContext::default(), 3).await.unwrap_err();
+        assert_eq!(err.code(), "teacher.not_found");
+    }
+}
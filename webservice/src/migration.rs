@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::{MySqlPool, SqlitePool};
+use std::io;
+
+/// 编译时嵌入的数据库迁移脚本，MySQL 和 SQLite 各有一套，版本号保持一致
+pub static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations/mysql");
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+#[async_trait]
+pub trait SchemaMigrator {
+    /// 应用所有尚未执行的迁移
+    async fn run_pending_migrations(&self) -> Result<(), MigrateError>;
+
+    /// 回滚版本号大于 target 的所有迁移
+    async fn revert_migrations_to(&self, target: i64) -> Result<(), MigrateError>;
+
+    /// 查询数据库当前记录的 schema 版本，尚未迁移时返回 None
+    async fn schema_version(&self) -> Result<Option<i64>, MigrateError>;
+
+    /// 该数据库对应的迁移脚本
+    fn migrator(&self) -> &'static Migrator;
+}
+
+#[async_trait]
+impl SchemaMigrator for MySqlPool {
+    async fn run_pending_migrations(&self) -> Result<(), MigrateError> {
+        MYSQL_MIGRATOR.run(self).await
+    }
+
+    async fn revert_migrations_to(&self, target: i64) -> Result<(), MigrateError> {
+        MYSQL_MIGRATOR.undo(self, target).await
+    }
+
+    async fn schema_version(&self) -> Result<Option<i64>, MigrateError> {
+        let mut conn = self.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        let applied = conn.list_applied_migrations().await?;
+
+        Ok(applied.iter().map(|m| m.version).max())
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MYSQL_MIGRATOR
+    }
+}
+
+#[async_trait]
+impl SchemaMigrator for SqlitePool {
+    async fn run_pending_migrations(&self) -> Result<(), MigrateError> {
+        SQLITE_MIGRATOR.run(self).await
+    }
+
+    async fn revert_migrations_to(&self, target: i64) -> Result<(), MigrateError> {
+        SQLITE_MIGRATOR.undo(self, target).await
+    }
+
+    async fn schema_version(&self) -> Result<Option<i64>, MigrateError> {
+        let mut conn = self.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        let applied = conn.list_applied_migrations().await?;
+
+        Ok(applied.iter().map(|m| m.version).max())
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &SQLITE_MIGRATOR
+    }
+}
+
+/// 当前版本的上一个版本，用于 `migrate down` 只回滚一步
+fn previous_version(migrator: &Migrator, current: i64) -> i64 {
+    migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .filter(|version| *version < current)
+        .max()
+        .unwrap_or(0)
+}
+
+/// 处理 `teacher_service migrate [up | down [version] | version]` 子命令
+pub async fn run_command<M>(db: &M, args: &[String]) -> io::Result<()>
+where
+    M: SchemaMigrator + ?Sized,
+{
+    let result = match args.first().map(String::as_str) {
+        None | Some("up") => db.run_pending_migrations().await,
+        Some("down") => {
+            let target = match args.get(1) {
+                Some(version) => version.parse::<i64>().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "Version must be an integer")
+                })?,
+                None => match db.schema_version().await.map_err(io::Error::other)? {
+                    Some(current) => previous_version(db.migrator(), current),
+                    None => 0,
+                },
+            };
+            db.revert_migrations_to(target).await
+        }
+        Some("version") => Ok(()),
+        Some(other) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown migrate command: {}", other),
+            ))
+        }
+    };
+    result.map_err(io::Error::other)?;
+
+    match db.schema_version().await.map_err(io::Error::other)? {
+        Some(version) => println!("Schema version: {}", version),
+        None => println!("Schema version: none"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use actix_web::dev::Payload;
+use actix_web::{FromRequest, HttpMessage, HttpRequest};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::future::{ready, Ready};
+use std::str::FromStr;
+use crate::auth::authenticate;
+use crate::errors::{MyError, RequestId};
+use crate::models::course::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+
+/// 审计日志记录的操作
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    /// 从回收站恢复
+    Restore,
+    /// 清理回收站时物理删除
+    Purge,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = MyError;
+
+    fn from_str(action: &str) -> Result<Self, Self::Err> {
+        match action {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            "restore" => Ok(AuditAction::Restore),
+            "purge" => Ok(AuditAction::Purge),
+            other => Err(MyError::InvalidInput(format!("Unknown audit action: {}", other))),
+        }
+    }
+}
+
+/// 被修改的实体类型
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEntity {
+    Teacher,
+    Course,
+    Module,
+    Lesson,
+    Review,
+    Enrollment,
+    Account,
+    Student,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Teacher => "teacher",
+            AuditEntity::Course => "course",
+            AuditEntity::Module => "module",
+            AuditEntity::Lesson => "lesson",
+            AuditEntity::Review => "review",
+            AuditEntity::Enrollment => "enrollment",
+            AuditEntity::Account => "account",
+            AuditEntity::Student => "student",
+        }
+    }
+}
+
+impl fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = MyError;
+
+    fn from_str(entity: &str) -> Result<Self, Self::Err> {
+        match entity {
+            "teacher" => Ok(AuditEntity::Teacher),
+            "course" => Ok(AuditEntity::Course),
+            "module" => Ok(AuditEntity::Module),
+            "lesson" => Ok(AuditEntity::Lesson),
+            "review" => Ok(AuditEntity::Review),
+            "enrollment" => Ok(AuditEntity::Enrollment),
+            "account" => Ok(AuditEntity::Account),
+            "student" => Ok(AuditEntity::Student),
+            other => Err(MyError::InvalidInput(format!("Unknown audit entity: {}", other))),
+        }
+    }
+}
+
+/// 执行修改的操作者和请求，处理器把它传给数据访问层写入审计日志
+///
+/// 作为处理器参数使用时不要求登录：未登录的请求（如注册）和后台任务的 actor_id 为 None
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor_id: Option<i32>,
+    pub request_id: Option<String>,
+}
+
+impl FromRequest for AuditContext {
+    type Error = MyError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(AuditContext {
+            actor_id: authenticate(req).ok().map(|user| user.account_id),
+            request_id: req.extensions().get::<RequestId>().map(|id| id.0.clone()),
+        }))
+    }
+}
+
+/// 一条待写入的审计记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub action: AuditAction,
+    pub entity: AuditEntity,
+    pub entity_id: i32,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl AuditRecord {
+    pub fn created(entity: AuditEntity, entity_id: i32, after: &impl Serialize) -> Self {
+        AuditRecord {
+            action: AuditAction::Create,
+            entity,
+            entity_id,
+            before: None,
+            after: Some(snapshot(after)),
+        }
+    }
+
+    /// 修改前后都只保留取值发生变化的字段
+    pub fn updated(entity: AuditEntity, entity_id: i32, before: &impl Serialize, after: &impl Serialize) -> Self {
+        let (before, after) = changed_fields(snapshot(before), snapshot(after));
+        AuditRecord {
+            action: AuditAction::Update,
+            entity,
+            entity_id,
+            before: Some(before),
+            after: Some(after),
+        }
+    }
+
+    pub fn deleted(entity: AuditEntity, entity_id: i32, before: &impl Serialize) -> Self {
+        AuditRecord {
+            action: AuditAction::Delete,
+            entity,
+            entity_id,
+            before: Some(snapshot(before)),
+            after: None,
+        }
+    }
+
+    /// 使用其他操作类型，例如把 deleted 记为 Purge
+    pub fn with_action(self, action: AuditAction) -> Self {
+        AuditRecord { action, ..self }
+    }
+}
+
+fn snapshot(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// 对比两个 JSON 对象，返回修改前后取值不同的字段；不是对象时原样返回
+fn changed_fields(before: Value, after: Value) -> (Value, Value) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(mut after)) => {
+            let mut old_values = Map::new();
+            let mut new_values = Map::new();
+            for (key, old_value) in before {
+                let new_value = after.remove(&key).unwrap_or(Value::Null);
+                if old_value != new_value {
+                    old_values.insert(key.clone(), old_value);
+                    new_values.insert(key, new_value);
+                }
+            }
+            // 修改后才出现的字段
+            for (key, new_value) in after {
+                old_values.insert(key.clone(), Value::Null);
+                new_values.insert(key, new_value);
+            }
+            (Value::Object(old_values), Value::Object(new_values))
+        }
+        (before, after) => (before, after),
+    }
+}
+
+/// 审计日志中的一条记录
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AuditEntry {
+    pub id: i32,
+    /// 操作者的账号 ID，未登录的请求和后台任务为 None
+    pub actor_id: Option<i32>,
+    pub action: AuditAction,
+    pub entity: AuditEntity,
+    pub entity_id: i32,
+    /// 修改前的值，新建时为 None
+    pub before: Option<Value>,
+    /// 修改后的值，删除时为 None
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// 审计日志的查询参数，例如 `/audit?entity=course&id=1`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub entity: Option<String>,
+    pub id: Option<i32>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl AuditQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0)
+    }
+
+    /// 已校验的实体类型，没有指定时为 None
+    pub fn entity(&self) -> Result<Option<AuditEntity>, MyError> {
+        self.entity
+            .as_deref()
+            .map(|entity| {
+                entity
+                    .parse()
+                    .map_err(|_err| MyError::invalid_field("entity", "Unknown entity"))
+            })
+            .transpose()
+    }
+
+    pub fn validate(&self) -> Result<(), MyError> {
+        if self.id.is_some() && self.entity()?.is_none() {
+            return Err(MyError::invalid_field("id", "entity is required when filtering by id"));
+        }
+        if !(1..=MAX_PAGE_SIZE).contains(&self.limit()) {
+            return Err(MyError::InvalidInput(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if self.offset() < 0 {
+            return Err(MyError::InvalidInput("offset must not be negative".into()));
+        }
+        Ok(())
+    }
+}
+
+/// 分页返回的审计日志，按时间倒序
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AuditPage {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// 下一页的链接，已经是最后一页时为 None
+    pub next: Option<String>,
+    pub items: Vec<AuditEntry>,
+}
@@ -0,0 +1,119 @@
+use actix_web::web;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use crate::errors::MyError;
+
+/// 登录账号，密码只保存 argon2 哈希
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Account {
+    pub id: i32,
+    pub username: String,
+    pub password_hash: String,
+    pub teacher_id: Option<i32>,
+    pub student_id: Option<i32>,
+}
+
+/// 账号角色：管理员管理所有教师，教师管理自己的课程，学生只能浏览和选课
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Teacher,
+    Student,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Teacher => "teacher",
+            Role::Student => "student",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = MyError;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            "admin" => Ok(Role::Admin),
+            "teacher" => Ok(Role::Teacher),
+            "student" => Ok(Role::Student),
+            other => Err(MyError::InvalidInput(format!("Unknown role: {}", other))),
+        }
+    }
+}
+
+/// 账号当前拥有的全部角色
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AccountRoles {
+    pub account_id: i32,
+    pub roles: Vec<Role>,
+}
+
+/// 注册教师账号：同时创建教师资料和登录账号
+#[derive(Deserialize, Debug, Clone)]
+pub struct RegisterTeacher {
+    pub username: String,
+    pub password: String,
+    pub name: String,
+    pub picture_url: String,
+    pub profile: String,
+}
+
+impl From<web::Json<RegisterTeacher>> for RegisterTeacher {
+    fn from(register: web::Json<RegisterTeacher>) -> Self {
+        register.into_inner()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// 登录和刷新成功后返回的令牌对
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    /// access token 的有效期（秒）
+    pub expires_in: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    Access,
+    Refresh,
+}
+
+/// JWT 中携带的声明
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Claims {
+    pub account_id: i32,
+    pub username: String,
+    pub teacher_id: Option<i32>,
+    #[serde(default)]
+    pub student_id: Option<i32>,
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    pub kind: TokenKind,
+    pub iat: i64,
+    pub exp: i64,
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use crate::errors::{MyError, MyErrorResponse};
+use crate::models::course::{Course, CreateCourse, UpdateCourse};
+
+/// 一次批量请求最多包含的操作数
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// 批量操作中的一项，`op` 为 create、update 或 delete
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOperation {
+    Create {
+        course: CreateCourse,
+    },
+    /// 与 PATCH 相同，只修改 course 中提供的字段
+    Update {
+        teacher_id: i32,
+        course_id: i32,
+        course: UpdateCourse,
+        /// 提供时只有版本号一致才修改，作用同 If-Match
+        version: Option<i32>,
+    },
+    Delete {
+        teacher_id: i32,
+        course_id: i32,
+        version: Option<i32>,
+    },
+}
+
+impl BatchOperation {
+    /// 操作涉及的教师，用于所有权检查
+    pub fn teacher_id(&self) -> i32 {
+        match self {
+            BatchOperation::Create { course } => course.teacher_id,
+            BatchOperation::Update { teacher_id, .. } | BatchOperation::Delete { teacher_id, .. } => {
+                *teacher_id
+            }
+        }
+    }
+}
+
+/// POST /courses/batch 的请求体
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatchRequest {
+    /// 为 true（默认）时全部操作在一个事务中执行，任一操作失败则全部回滚；
+    /// 为 false 时逐个执行，失败的操作不影响其他操作
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+    pub operations: Vec<BatchOperation>,
+}
+
+fn default_atomic() -> bool {
+    true
+}
+
+impl BatchRequest {
+    pub fn validate(&self) -> Result<(), MyError> {
+        if self.operations.is_empty() {
+            return Err(MyError::invalid_field("operations", "must not be empty"));
+        }
+        if self.operations.len() > MAX_BATCH_SIZE {
+            return Err(MyError::invalid_field(
+                "operations",
+                &format!("must contain at most {} operations", MAX_BATCH_SIZE),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 单个操作的结果，status 与单独调用对应接口时的状态码相同
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BatchResult {
+    pub status: u16,
+    /// 新建或修改后的课程，删除和失败时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub course: Option<Course>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<MyErrorResponse>,
+}
+
+/// 批量操作的结果，results 与请求中的 operations 一一对应
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BatchResponse {
+    pub atomic: bool,
+    /// 是否有修改被提交；原子模式下任一操作失败时为 false
+    pub committed: bool,
+    pub results: Vec<BatchResult>,
+}
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use crate::errors::{FieldError, MyError};
+use crate::models::course::{Course, CreateCourse};
+
+/// 一次最多导入的课程数
+pub const MAX_IMPORT_ROWS: usize = 1000;
+/// 导入请求体的大小上限：每行按 16 KiB 计算，足够容纳最长的描述（含 UTF-8 多字节字符和 JSON 转义）
+pub const MAX_IMPORT_BYTES: usize = MAX_IMPORT_ROWS * 16 * 1024;
+/// CSV 的表头，与 CreateCourse 的字段顺序一致
+const CSV_COLUMNS: [&str; 9] = [
+    "teacher_id",
+    "name",
+    "time",
+    "description",
+    "format",
+    "price",
+    "language",
+    "level",
+    "capacity",
+];
+
+/// 课程目录导入导出的文件格式，两种格式的列与 CreateCourse 的字段一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogFormat {
+    Csv,
+    Json,
+}
+
+impl CatalogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CatalogFormat::Csv => "csv",
+            CatalogFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            CatalogFormat::Csv => "text/csv; charset=utf-8",
+            CatalogFormat::Json => "application/json",
+        }
+    }
+
+    /// 根据导入请求的 Content-Type 判断格式
+    pub fn from_mime(mime: Option<&str>) -> Result<Self, MyError> {
+        match mime {
+            Some("text/csv") => Ok(CatalogFormat::Csv),
+            Some("application/json") => Ok(CatalogFormat::Json),
+            _ => Err(MyError::InvalidInput(
+                "Content-Type must be text/csv or application/json".into(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CatalogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CatalogFormat {
+    type Err = MyError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(CatalogFormat::Csv),
+            "json" => Ok(CatalogFormat::Json),
+            _ => Err(MyError::InvalidInput(format!("Unknown format: {}", format))),
+        }
+    }
+}
+
+/// GET /courses/{teacher_id}/export 的查询参数，缺省时导出 JSON
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
+
+impl ExportQuery {
+    pub fn format(&self) -> Result<CatalogFormat, MyError> {
+        self.format
+            .as_deref()
+            .map(str::parse)
+            .unwrap_or(Ok(CatalogFormat::Json))
+    }
+}
+
+/// POST /courses/{teacher_id}/import 的查询参数
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ImportQuery {
+    /// 为 true 时只校验不写入
+    pub dry_run: Option<bool>,
+}
+
+impl ImportQuery {
+    pub fn dry_run(&self) -> bool {
+        self.dry_run.unwrap_or(false)
+    }
+}
+
+/// 导入结果
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// 文件中的课程数
+    pub total: usize,
+    /// 各行的错误，field 形如 `rows[2].price`（行号从 0 开始，不含 CSV 表头）
+    pub errors: Vec<FieldError>,
+    /// 已导入的课程，试运行时为空
+    pub courses: Vec<Course>,
+}
+
+/// 导出时每门课程输出的列，导出的文件可以直接再导入
+impl From<Course> for CreateCourse {
+    fn from(course: Course) -> Self {
+        CreateCourse {
+            teacher_id: course.teacher_id,
+            name: course.name,
+            time: course.time,
+            description: course.description,
+            format: course.format.map(|format| format.to_string()),
+            price: course.price,
+            language: course.language.map(|language| language.to_string()),
+            level: course.level.map(|level| level.to_string()),
+            capacity: course.capacity,
+        }
+    }
+}
+
+/// 一行解析失败时的错误，field 为 None 表示无法定位到具体的列
+type RowError = (Option<String>, String);
+
+fn row_field(row: usize, field: Option<&str>) -> String {
+    match field {
+        Some(field) => format!("rows[{}].{}", row, field),
+        None => format!("rows[{}]", row),
+    }
+}
+
+/// 解析导入的文件，单行解析失败不影响其他行，整个文件无法解析时返回 InvalidInput
+pub fn parse_courses(format: CatalogFormat, body: &[u8]) -> Result<Vec<Result<CreateCourse, RowError>>, MyError> {
+    let rows = match format {
+        CatalogFormat::Json => {
+            let values: Vec<serde_json::Value> = serde_json::from_slice(body)
+                .map_err(|err| MyError::InvalidInput(format!("Invalid JSON array: {}", err)))?;
+            values
+                .into_iter()
+                .map(|value| serde_json::from_value(value).map_err(|err| (None, err.to_string())))
+                .collect::<Vec<_>>()
+        }
+        CatalogFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(body);
+            let headers = reader
+                .headers()
+                .map_err(|err| MyError::InvalidInput(format!("Invalid CSV header: {}", err)))?
+                .clone();
+            reader
+                .deserialize()
+                .map(|row| row.map_err(|err| csv_row_error(&headers, err)))
+                .collect()
+        }
+    };
+
+    if rows.is_empty() {
+        return Err(MyError::InvalidInput("No courses to import".into()));
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(MyError::InvalidInput(format!(
+            "At most {} courses can be imported at once",
+            MAX_IMPORT_ROWS
+        )));
+    }
+    Ok(rows)
+}
+
+/// 把 CSV 的解析错误对应到表头中的列名
+fn csv_row_error(headers: &csv::StringRecord, err: csv::Error) -> RowError {
+    match err.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => {
+            let field = err
+                .field()
+                .and_then(|index| headers.get(index as usize))
+                .map(String::from);
+            (field, err.kind().to_string())
+        }
+        _ => (None, err.to_string()),
+    }
+}
+
+/// 逐行校验并规范化，返回通过校验的课程和全部错误；teacher_id 必须与 URL 中的教师一致
+pub fn validate_courses(
+    teacher_id: i32,
+    rows: Vec<Result<CreateCourse, RowError>>,
+) -> (Vec<CreateCourse>, Vec<FieldError>) {
+    let mut courses = vec![];
+    let mut errors = vec![];
+    for (row, course) in rows.into_iter().enumerate() {
+        let course = match course {
+            Ok(course) => course,
+            Err((field, message)) => {
+                errors.push(FieldError {
+                    field: row_field(row, field.as_deref()),
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let mut validator = course.validator();
+        if course.teacher_id != teacher_id {
+            validator = validator.add("teacher_id", "must match the teacher in the URL");
+        }
+        match validator.finish().and_then(|_| course.canonicalize()) {
+            Ok(course) => courses.push(course),
+            Err(MyError::Validation(field_errors)) => {
+                errors.extend(field_errors.into_iter().map(|error| FieldError {
+                    field: row_field(row, Some(&error.field)),
+                    message: error.message,
+                }));
+            }
+            // canonicalize 只会返回 InvalidInput，取值已经由 validator 检查过
+            Err(MyError::InvalidInput(message)) => errors.push(FieldError {
+                field: row_field(row, None),
+                message,
+            }),
+            Err(err) => errors.push(FieldError {
+                field: row_field(row, None),
+                message: err.to_string(),
+            }),
+        }
+    }
+    (courses, errors)
+}
+
+/// 按导入时的列输出课程
+pub fn write_courses(format: CatalogFormat, courses: Vec<Course>) -> Result<Vec<u8>, MyError> {
+    let rows: Vec<CreateCourse> = courses.into_iter().map(CreateCourse::from).collect();
+    match format {
+        CatalogFormat::Json => {
+            serde_json::to_vec(&rows).map_err(|err| MyError::ActixError(err.to_string()))
+        }
+        CatalogFormat::Csv => {
+            // 没有课程时也输出表头
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(vec![]);
+            writer
+                .write_record(CSV_COLUMNS)
+                .map_err(|err| MyError::ActixError(err.to_string()))?;
+            for row in &rows {
+                writer
+                    .serialize(row)
+                    .map_err(|err| MyError::ActixError(err.to_string()))?;
+            }
+            writer
+                .into_inner()
+                .map_err(|err| MyError::ActixError(err.to_string()))
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use actix_web::web;
+use serde::{Deserialize, Serialize};
+use crate::errors::MyError;
+use crate::models::course::deserialize_nullable;
+
+/// 课程中的一个章节，position 越小越靠前
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct CourseModule {
+    pub id: i32,
+    pub course_id: i32,
+    pub title: String,
+    pub position: i32,
+}
+
+/// 章节中的一个课时，position 越小越靠前
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct Lesson {
+    pub id: i32,
+    pub module_id: i32,
+    pub title: String,
+    pub body: Option<String>,
+    /// 预计学习时长（分钟）
+    pub estimated_minutes: i32,
+    pub attachment_url: Option<String>,
+    pub position: i32,
+}
+
+/// 章节及其全部课时，duration 为课时预计时长之和（分钟）
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ModuleOutline {
+    #[serde(flatten)]
+    pub module: CourseModule,
+    pub duration: i64,
+    pub lessons: Vec<Lesson>,
+}
+
+impl ModuleOutline {
+    pub fn new(module: CourseModule, lessons: Vec<Lesson>) -> Self {
+        let duration = lessons
+            .iter()
+            .map(|lesson| i64::from(lesson.estimated_minutes))
+            .sum();
+        ModuleOutline {
+            module,
+            duration,
+            lessons,
+        }
+    }
+}
+
+/// 新建章节，追加到课程的最后
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateModule {
+    pub title: String,
+}
+
+impl From<web::Json<CreateModule>> for CreateModule {
+    fn from(module: web::Json<CreateModule>) -> Self {
+        module.into_inner()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateModule {
+    pub title: Option<String>,
+}
+
+impl From<web::Json<UpdateModule>> for UpdateModule {
+    fn from(module: web::Json<UpdateModule>) -> Self {
+        module.into_inner()
+    }
+}
+
+/// 新建课时，追加到章节的最后
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateLesson {
+    pub title: String,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub estimated_minutes: i32,
+    pub attachment_url: Option<String>,
+}
+
+impl TryFrom<web::Json<CreateLesson>> for CreateLesson {
+    type Error = MyError;
+
+    fn try_from(lesson: web::Json<CreateLesson>) -> Result<Self, Self::Error> {
+        let lesson = lesson.into_inner();
+        validate_minutes(lesson.estimated_minutes)?;
+        Ok(lesson)
+    }
+}
+
+/// 部分修改课时：缺省的字段保持不变，显式传 null 的字段会被清空
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct UpdateLesson {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub body: Option<Option<String>>,
+    pub estimated_minutes: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub attachment_url: Option<Option<String>>,
+}
+
+impl TryFrom<web::Json<UpdateLesson>> for UpdateLesson {
+    type Error = MyError;
+
+    fn try_from(lesson: web::Json<UpdateLesson>) -> Result<Self, Self::Error> {
+        let lesson = lesson.into_inner();
+        if let Some(minutes) = lesson.estimated_minutes {
+            validate_minutes(minutes)?;
+        }
+        Ok(lesson)
+    }
+}
+
+fn validate_minutes(minutes: i32) -> Result<(), MyError> {
+    if minutes < 0 {
+        return Err(MyError::InvalidInput("estimated_minutes must not be negative".into()));
+    }
+    Ok(())
+}
+
+/// 调整顺序：按新的顺序列出全部章节或课时的 id
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Reorder {
+    pub ids: Vec<i32>,
+}
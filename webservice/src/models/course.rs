@@ -1,10 +1,12 @@
 use actix_web::web;
 use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::convert::TryFrom;
 use crate::errors::MyError;
+use crate::models::course_option::{canonical, CourseFormat, CourseLanguage, CourseLevel};
+use crate::validation::Validator;
 
-#[derive(Serialize, sqlx::FromRow, Debug, Clone)]
+#[derive(Deserialize, Serialize, sqlx::FromRow, Debug, Clone)]
 pub struct Course {
     pub teacher_id: i32,
     pub id: i32,
@@ -12,27 +14,35 @@ pub struct Course {
     pub time: Option<NaiveDateTime>,
 
     pub description: Option<String>,
-    pub format: Option<String>,
-    pub structure: Option<String>,
-    pub duration: Option<String>,
+    pub format: Option<CourseFormat>,
+    /// 课程总时长（分钟），由全部课时的预计时长相加得到
+    pub duration: i64,
     pub price: Option<i32>,
-    pub language: Option<String>,
-    pub level: Option<String>,
+    pub language: Option<CourseLanguage>,
+    pub level: Option<CourseLevel>,
+    /// 课程容量，None 表示不限人数
+    pub capacity: Option<i32>,
+    /// 平均评分，没有评价时为 None
+    pub avg_rating: Option<f64>,
+    pub review_count: i64,
+    /// 版本号，每次修改加一，用于 If-Match 条件写入
+    pub version: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
 }
 
 /// 新建课程
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CreateCourse {
     pub teacher_id: i32,
     pub name: String,
     pub time: Option<NaiveDateTime>,
     pub description: Option<String>,
     pub format: Option<String>,
-    pub structure: Option<String>,
-    pub duration: Option<String>,
     pub price: Option<i32>,
     pub language: Option<String>,
     pub level: Option<String>,
+    pub capacity: Option<i32>,
 }
 
 // impl From<web::Json<CreateCourse>> for CreateCourse {
@@ -51,51 +61,239 @@ pub struct CreateCourse {
 //     }
 // }
 
-impl TryFrom<web::Json<CreateCourse>> for CreateCourse {
-    type Error = MyError;
+/// 课程名称的最大长度，与 course.name 列一致
+pub const MAX_NAME_LENGTH: usize = 140;
+/// 课程描述的最大长度，与 course.description 列一致
+pub const MAX_DESCRIPTION_LENGTH: usize = 2000;
+
+impl CreateCourse {
+    /// 校验各字段并收集全部错误，调用方可以继续追加需要查询数据库的检查
+    pub fn validator(&self) -> Validator {
+        Validator::new()
+            .required("name", &self.name, MAX_NAME_LENGTH)
+            .max_length("description", self.description.as_deref(), MAX_DESCRIPTION_LENGTH)
+            .one_of::<CourseFormat>("format", self.format.as_deref())
+            .one_of::<CourseLanguage>("language", self.language.as_deref())
+            .one_of::<CourseLevel>("level", self.level.as_deref())
+            .at_least("price", self.price, 0)
+            .at_least("capacity", self.capacity, 1)
+    }
 
-    fn try_from(course: web::Json<CreateCourse>) -> Result<Self, Self::Error> {
+    /// 把 format、language、level 规范化，未知的值返回 InvalidInput
+    pub fn canonicalize(self) -> Result<Self, MyError> {
         Ok(CreateCourse {
-            teacher_id: course.teacher_id,
-            name: course.name.clone(),
-            time: course.time.clone(),
-            description: course.description.clone(),
-            format: course.format.clone(),
-            structure: course.structure.clone(),
-            duration: course.duration.clone(),
-            price: course.price,
-            language: course.language.clone(),
-            level: course.level.clone(),
+            format: canonical::<CourseFormat>(self.format)?,
+            language: canonical::<CourseLanguage>(self.language)?,
+            level: canonical::<CourseLevel>(self.level)?,
+            ..self
         })
     }
 }
 
-/// 修改课程
-#[derive(Deserialize, Debug, Clone)]
+/// 部分修改课程（PATCH）：缺省的字段保持不变，显式传 null 的字段会被清空
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct UpdateCourse {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub time: Option<Option<NaiveDateTime>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub format: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub price: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub language: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub level: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub capacity: Option<Option<i32>>,
+}
+
+/// PATCH 中提供了非 null 值的文本字段
+fn nested(value: &Option<Option<String>>) -> Option<&str> {
+    value.as_ref().and_then(|value| value.as_deref())
+}
+
+/// 字段存在时（包括 null）包一层 Some，配合 `#[serde(default)]` 区分缺省和 null
+pub(crate) fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl UpdateCourse {
+    /// 没有任何需要修改的字段
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.time.is_none()
+            && self.description.is_none()
+            && self.format.is_none()
+            && self.price.is_none()
+            && self.language.is_none()
+            && self.level.is_none()
+            && self.capacity.is_none()
+    }
+}
+
+impl UpdateCourse {
+    /// 只校验请求中提供的字段，显式传 null 的字段不做检查
+    pub fn validator(&self) -> Validator {
+        Validator::new()
+            .optional("name", self.name.as_deref(), MAX_NAME_LENGTH)
+            .max_length("description", nested(&self.description), MAX_DESCRIPTION_LENGTH)
+            .one_of::<CourseFormat>("format", nested(&self.format))
+            .one_of::<CourseLanguage>("language", nested(&self.language))
+            .one_of::<CourseLevel>("level", nested(&self.level))
+            .at_least("price", self.price.flatten(), 0)
+            .at_least("capacity", self.capacity.flatten(), 1)
+    }
+
+    /// 校验通过后把 format、language、level 规范化
+    pub fn canonicalize(mut self) -> Result<Self, MyError> {
+        self.validator().finish()?;
+        self.format = self.format.map(canonical::<CourseFormat>).transpose()?;
+        self.language = self.language.map(canonical::<CourseLanguage>).transpose()?;
+        self.level = self.level.map(canonical::<CourseLevel>).transpose()?;
+        Ok(self)
+    }
+}
+
+impl TryFrom<web::Json<UpdateCourse>> for UpdateCourse {
+    type Error = MyError;
+
+    fn try_from(course: web::Json<UpdateCourse>) -> Result<Self, Self::Error> {
+        course.into_inner().canonicalize()
+    }
+}
+
+/// 整体替换课程（PUT）：未提供的可选字段会被置为 null
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReplaceCourse {
+    pub name: String,
     pub time: Option<NaiveDateTime>,
     pub description: Option<String>,
     pub format: Option<String>,
-    pub structure: Option<String>,
-    pub duration: Option<String>,
     pub price: Option<i32>,
     pub language: Option<String>,
     pub level: Option<String>,
+    pub capacity: Option<i32>,
 }
 
-impl From<web::Json<UpdateCourse>> for UpdateCourse {
-    fn from(course: web::Json<UpdateCourse>) -> Self {
+impl TryFrom<web::Json<ReplaceCourse>> for UpdateCourse {
+    type Error = MyError;
+
+    fn try_from(course: web::Json<ReplaceCourse>) -> Result<Self, Self::Error> {
+        let course = course.into_inner();
         UpdateCourse {
-            name: course.name.clone(),
-            time: course.time.clone(),
-            description: course.description.clone(),
-            format: course.format.clone(),
-            structure: course.structure.clone(),
-            duration: course.duration.clone(),
-            price: course.price,
-            language: course.language.clone(),
-            level: course.level.clone(),
+            name: Some(course.name),
+            time: Some(course.time),
+            description: Some(course.description),
+            format: Some(course.format),
+            price: Some(course.price),
+            language: Some(course.language),
+            level: Some(course.level),
+            capacity: Some(course.capacity),
+        }
+        .canonicalize()
+    }
+}
+
+/// 每页默认返回的课程数量
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+/// 每页最多返回的课程数量
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// 课程列表的分页、过滤和排序参数
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CourseQuery {
+    /// 在 name、description 中全文检索的关键字
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub level: Option<String>,
+    pub language: Option<String>,
+    pub format: Option<String>,
+    pub min_price: Option<i32>,
+    pub max_price: Option<i32>,
+    pub time_from: Option<NaiveDateTime>,
+    pub time_to: Option<NaiveDateTime>,
+    /// name、time、price 之一，前缀 `-` 表示降序
+    pub sort: Option<String>,
+}
+
+impl CourseQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0)
+    }
+
+    /// 去掉首尾空白后的检索关键字，为空时视为不检索
+    pub fn search_text(&self) -> Option<&str> {
+        self.q
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+    }
+
+    pub fn validate(&self) -> Result<(), MyError> {
+        if !(1..=MAX_PAGE_SIZE).contains(&self.limit()) {
+            return Err(MyError::InvalidInput(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
         }
+        if self.offset() < 0 {
+            return Err(MyError::InvalidInput("offset must not be negative".into()));
+        }
+        self.level()?;
+        self.language()?;
+        self.format()?;
+        self.order_by().map(|_| ())
+    }
+
+    pub fn level(&self) -> Result<Option<CourseLevel>, MyError> {
+        self.level.as_deref().map(str::parse).transpose()
+    }
+
+    pub fn language(&self) -> Result<Option<CourseLanguage>, MyError> {
+        self.language.as_deref().map(str::parse).transpose()
     }
-}
\ No newline at end of file
+
+    pub fn format(&self) -> Result<Option<CourseFormat>, MyError> {
+        self.format.as_deref().map(str::parse).transpose()
+    }
+
+    /// 排序参数对应的 ORDER BY 子句，只接受白名单中的列，最后按 id 排序保证分页稳定
+    pub fn order_by(&self) -> Result<&'static str, MyError> {
+        match self.sort.as_deref() {
+            None => Ok("id"),
+            Some("name") => Ok("name, id"),
+            Some("-name") => Ok("name DESC, id"),
+            Some("time") => Ok("time, id"),
+            Some("-time") => Ok("time DESC, id"),
+            Some("price") => Ok("price, id"),
+            Some("-price") => Ok("price DESC, id"),
+            Some(other) => Err(MyError::InvalidInput(format!(
+                "Unknown sort field: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 分页返回的课程列表
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CoursePage {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// 下一页的链接，已经是最后一页时为 None
+    pub next: Option<String>,
+    pub items: Vec<Course>,
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use sqlx::database::{HasArguments, HasValueRef};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type};
+use std::fmt;
+use std::str::FromStr;
+use crate::errors::MyError;
+
+/// 为课程的可选值生成枚举：序列化和入库都使用规范值（即变体名），
+/// 解析时不区分大小写，并接受中文名称和别名，未知的值返回 InvalidInput 并指出字段名
+macro_rules! course_option {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $field:literal {
+            $($variant:ident => $label:literal $(| $alias:literal)*),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),+
+                }
+            }
+
+            /// 中文名称，用于下拉框显示
+            pub fn label(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $label),+
+                }
+            }
+
+            /// 全部可选值，按定义顺序排列
+            pub fn options() -> Vec<CourseOption> {
+                Self::ALL
+                    .iter()
+                    .map(|option| CourseOption {
+                        value: option.as_str().into(),
+                        label: option.label().into(),
+                    })
+                    .collect()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = MyError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let trimmed = value.trim();
+                $(
+                    if trimmed.eq_ignore_ascii_case(stringify!($variant))
+                        || trimmed == $label
+                        $(|| trimmed == $alias)*
+                    {
+                        return Ok($name::$variant);
+                    }
+                )+
+                Err(MyError::InvalidInput(format!("Unknown {}: {}", $field, value)))
+            }
+        }
+
+        impl<DB: Database> Type<DB> for $name
+        where
+            String: Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <String as Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <String as Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'r, DB: Database> Decode<'r, DB> for $name
+        where
+            String: Decode<'r, DB>,
+        {
+            fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+                let value = <String as Decode<'r, DB>>::decode(value)?;
+                value
+                    .parse()
+                    .map_err(|_err| format!("Unknown {} in database: {}", $field, value).into())
+            }
+        }
+
+        impl<'q, DB: Database> Encode<'q, DB> for $name
+        where
+            String: Encode<'q, DB>,
+        {
+            fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+                <String as Encode<'q, DB>>::encode(self.as_str().to_string(), buf)
+            }
+        }
+    };
+}
+
+course_option! {
+    /// 课程难度
+    CourseLevel, "level" {
+        Beginner => "初级" | "入门",
+        Intermediate => "中级",
+        Advanced => "高级",
+    }
+}
+
+course_option! {
+    /// 授课形式
+    CourseFormat, "format" {
+        Video => "视频" | "录播",
+        Live => "直播",
+        Text => "图文",
+        Blended => "混合",
+    }
+}
+
+course_option! {
+    /// 授课语言
+    CourseLanguage, "language" {
+        English => "英语" | "英文",
+        Chinese => "中文" | "汉语",
+        Japanese => "日语",
+        Korean => "韩语",
+        French => "法语",
+        German => "德语",
+        Spanish => "西班牙语",
+    }
+}
+
+/// 下拉框中的一个选项
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CourseOption {
+    pub value: String,
+    pub label: String,
+}
+
+/// 课程各个可选字段的全部取值
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CourseOptions {
+    pub levels: Vec<CourseOption>,
+    pub formats: Vec<CourseOption>,
+    pub languages: Vec<CourseOption>,
+}
+
+impl CourseOptions {
+    pub fn all() -> Self {
+        CourseOptions {
+            levels: CourseLevel::options(),
+            formats: CourseFormat::options(),
+            languages: CourseLanguage::options(),
+        }
+    }
+}
+
+/// 把请求中的原始字符串规范化，未知的值返回 InvalidInput
+pub(crate) fn canonical<T>(value: Option<String>) -> Result<Option<String>, MyError>
+where
+    T: FromStr<Err = MyError> + fmt::Display,
+{
+    value
+        .map(|value| value.parse::<T>().map(|option| option.to_string()))
+        .transpose()
+}
@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use crate::models::course::Course;
+
+/// 选课状态：课程已满时进入候补名单，有人退课后按顺序递补
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnrollmentStatus {
+    Enrolled,
+    Waitlisted,
+}
+
+impl EnrollmentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnrollmentStatus::Enrolled => "enrolled",
+            EnrollmentStatus::Waitlisted => "waitlisted",
+        }
+    }
+}
+
+impl TryFrom<String> for EnrollmentStatus {
+    type Error = String;
+
+    fn try_from(status: String) -> Result<Self, Self::Error> {
+        match status.as_str() {
+            "enrolled" => Ok(EnrollmentStatus::Enrolled),
+            "waitlisted" => Ok(EnrollmentStatus::Waitlisted),
+            _ => Err(format!("Unknown enrollment status: {}", status)),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct Enrollment {
+    pub id: i32,
+    pub course_id: i32,
+    pub student_id: i32,
+    #[sqlx(try_from = "String")]
+    pub status: EnrollmentStatus,
+    pub enrolled_at: NaiveDateTime,
+}
+
+/// 课程名单中的一条记录
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct RosterEntry {
+    pub student_id: i32,
+    pub name: String,
+    pub email: String,
+    #[sqlx(try_from = "String")]
+    pub status: EnrollmentStatus,
+    pub enrolled_at: NaiveDateTime,
+}
+
+/// 课程名单：已选课的学生和按顺序排列的候补名单
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CourseRoster {
+    pub course_id: i32,
+    pub capacity: Option<i32>,
+    pub enrolled: Vec<RosterEntry>,
+    pub waitlist: Vec<RosterEntry>,
+}
+
+/// 学生选修的课程及其选课状态
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct StudentCourse {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub course: Course,
+    #[sqlx(try_from = "String")]
+    pub status: EnrollmentStatus,
+    pub enrolled_at: NaiveDateTime,
+}
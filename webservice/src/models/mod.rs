@@ -1,2 +1,12 @@
+pub mod audit;
+pub mod auth;
+pub mod batch;
+pub mod catalog;
+pub mod content;
 pub mod course;
-pub mod teacher;
\ No newline at end of file
+pub mod course_option;
+pub mod enrollment;
+pub mod review;
+pub mod student;
+pub mod teacher;
+pub mod trash;
@@ -0,0 +1,118 @@
+use actix_web::web;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::errors::MyError;
+use crate::models::course::{deserialize_nullable, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::validation::Validator;
+
+/// 评分的取值范围
+pub const MIN_RATING: i32 = 1;
+pub const MAX_RATING: i32 = 5;
+
+/// 学生对课程的评价，student_name 来自 student 表
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct Review {
+    pub id: i32,
+    pub course_id: i32,
+    pub student_id: i32,
+    pub student_name: String,
+    pub rating: i32,
+    pub comment: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 新建评价
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateReview {
+    pub rating: i32,
+    pub comment: Option<String>,
+}
+
+impl CreateReview {
+    pub fn validator(&self) -> Validator {
+        Validator::new().between("rating", Some(self.rating), MIN_RATING, MAX_RATING)
+    }
+}
+
+impl TryFrom<web::Json<CreateReview>> for CreateReview {
+    type Error = MyError;
+
+    fn try_from(review: web::Json<CreateReview>) -> Result<Self, Self::Error> {
+        let review = review.into_inner();
+        review.validator().finish()?;
+        Ok(review)
+    }
+}
+
+/// 修改评价：缺省的字段保持不变，comment 显式传 null 时清空评语
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateReview {
+    pub rating: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub comment: Option<Option<String>>,
+}
+
+impl UpdateReview {
+    pub fn validator(&self) -> Validator {
+        Validator::new().between("rating", self.rating, MIN_RATING, MAX_RATING)
+    }
+}
+
+impl TryFrom<web::Json<UpdateReview>> for UpdateReview {
+    type Error = MyError;
+
+    fn try_from(review: web::Json<UpdateReview>) -> Result<Self, Self::Error> {
+        let review = review.into_inner();
+        review.validator().finish()?;
+        Ok(review)
+    }
+}
+
+/// 评价列表的分页参数，默认值和上限与课程列表一致
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ReviewQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl ReviewQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0)
+    }
+
+    pub fn validate(&self) -> Result<(), MyError> {
+        if !(1..=MAX_PAGE_SIZE).contains(&self.limit()) {
+            return Err(MyError::InvalidInput(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if self.offset() < 0 {
+            return Err(MyError::InvalidInput("offset must not be negative".into()));
+        }
+        Ok(())
+    }
+}
+
+/// 分页返回的评价列表，按时间倒序
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ReviewPage {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// 下一页的链接，已经是最后一页时为 None
+    pub next: Option<String>,
+    pub items: Vec<Review>,
+}
+
+/// 评价汇总：平均分和评价数量，没有评价时平均分为 None
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct RatingSummary {
+    pub avg_rating: Option<f64>,
+    pub review_count: i64,
+}
@@ -0,0 +1,31 @@
+use actix_web::web;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct Student {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+}
+
+/// 注册学生账号：同时创建学生资料和登录账号
+#[derive(Deserialize, Debug, Clone)]
+pub struct RegisterStudent {
+    pub username: String,
+    pub password: String,
+    pub name: String,
+    pub email: String,
+}
+
+impl From<web::Json<RegisterStudent>> for RegisterStudent {
+    fn from(register: web::Json<RegisterStudent>) -> Self {
+        register.into_inner()
+    }
+}
+
+/// 新建学生资料，登录账号的字段由 RegisterStudent 单独提供
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateStudent {
+    pub name: String,
+    pub email: String,
+}
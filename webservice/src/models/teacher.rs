@@ -1,5 +1,14 @@
 use actix_web::web;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use crate::errors::MyError;
+use crate::models::review::RatingSummary;
+use crate::validation::Validator;
+
+/// 各字段的最大长度，与 teacher 表的列一致
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_PICTURE_URL_LENGTH: usize = 200;
+pub const MAX_PROFILE_LENGTH: usize = 2000;
 
 #[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
 pub struct Teacher {
@@ -7,6 +16,19 @@ pub struct Teacher {
     pub name: String,
     pub picture_url: String,
     pub profile: String,
+    /// 版本号，每次修改加一，用于 If-Match 条件写入
+    pub version: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 教师详情：附带其全部课程的评价汇总
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TeacherDetail {
+    #[serde(flatten)]
+    pub teacher: Teacher,
+    #[serde(flatten)]
+    pub rating: RatingSummary,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -16,13 +38,22 @@ pub struct CreateTeacher {
     pub profile: String,
 }
 
-impl From<web::Json<CreateTeacher>> for CreateTeacher {
-    fn from(new_teacher: web::Json<CreateTeacher>) -> Self {
-        CreateTeacher {
-            name: new_teacher.name.clone(),
-            picture_url: new_teacher.picture_url.clone(),
-            profile: new_teacher.profile.clone(),
-        }
+impl CreateTeacher {
+    pub fn validator(&self) -> Validator {
+        Validator::new()
+            .required("name", &self.name, MAX_NAME_LENGTH)
+            .required("picture_url", &self.picture_url, MAX_PICTURE_URL_LENGTH)
+            .url("picture_url", Some(&self.picture_url))
+            .max_length("profile", Some(&self.profile), MAX_PROFILE_LENGTH)
+    }
+}
+
+impl TryFrom<web::Json<CreateTeacher>> for CreateTeacher {
+    type Error = MyError;
+
+    fn try_from(new_teacher: web::Json<CreateTeacher>) -> Result<Self, Self::Error> {
+        new_teacher.validator().finish()?;
+        Ok(new_teacher.into_inner())
     }
 }
 
@@ -33,12 +64,55 @@ pub struct UpdateTeacher {
     pub profile: Option<String>,
 }
 
-impl From<web::Json<UpdateTeacher>> for UpdateTeacher {
-    fn from(update_teacher: web::Json<UpdateTeacher>) -> Self {
-        UpdateTeacher {
-            name: update_teacher.name.clone(),
-            picture_url: update_teacher.picture_url.clone(),
-            profile: update_teacher.profile.clone(),
-        }
+impl TryFrom<web::Json<UpdateTeacher>> for UpdateTeacher {
+    type Error = MyError;
+
+    fn try_from(update_teacher: web::Json<UpdateTeacher>) -> Result<Self, Self::Error> {
+        Validator::new()
+            .optional("name", update_teacher.name.as_deref(), MAX_NAME_LENGTH)
+            .optional("picture_url", update_teacher.picture_url.as_deref(), MAX_PICTURE_URL_LENGTH)
+            .url("picture_url", update_teacher.picture_url.as_deref())
+            .max_length("profile", update_teacher.profile.as_deref(), MAX_PROFILE_LENGTH)
+            .finish()?;
+        Ok(update_teacher.into_inner())
+    }
+}
+
+/// 删除教师时如何处理其名下的课程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// 仍有课程时拒绝删除（默认）
+    Reject,
+    /// 在同一事务中把全部课程一起移入回收站，选课、评价和章节等数据保留，恢复教师时课程一并恢复
+    Cascade,
+    /// 把全部课程转给另一位教师
+    Reassign(i32),
+}
+
+/// DELETE /teachers/{teacher_id} 的查询参数，例如 `?on_courses=reassign&reassign_to=2`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DeleteTeacherQuery {
+    pub on_courses: Option<String>,
+    pub reassign_to: Option<i32>,
+}
+
+impl DeleteTeacherQuery {
+    /// 解析删除策略，参数组合不合法时返回 Validation
+    pub fn policy(&self, teacher_id: i32) -> Result<DeletePolicy, MyError> {
+        let on_courses = self.on_courses.as_deref().map(str::to_ascii_lowercase);
+        let (field, message) = match (on_courses.as_deref(), self.reassign_to) {
+            (None | Some("reject"), None) => return Ok(DeletePolicy::Reject),
+            (Some("cascade"), None) => return Ok(DeletePolicy::Cascade),
+            (Some("reassign"), Some(reassign_to)) if reassign_to != teacher_id => {
+                return Ok(DeletePolicy::Reassign(reassign_to))
+            }
+            (Some("reassign"), Some(_)) => ("reassign_to", "must be a different teacher"),
+            (Some("reassign"), None) => ("reassign_to", "is required when on_courses is reassign"),
+            (None | Some("reject" | "cascade"), Some(_)) => {
+                ("reassign_to", "is only allowed when on_courses is reassign")
+            }
+            (Some(_), _) => ("on_courses", "must be one of reject, cascade, reassign"),
+        };
+        Err(MyError::invalid_field(field, message))
     }
-}
\ No newline at end of file
+}
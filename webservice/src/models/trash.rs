@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// 回收站中的教师
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct DeletedTeacher {
+    pub id: i32,
+    pub name: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// 回收站中的课程
+#[derive(Deserialize, Serialize, Debug, Clone, sqlx::FromRow)]
+pub struct DeletedCourse {
+    pub teacher_id: i32,
+    pub id: i32,
+    pub name: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// 回收站内容，按删除时间从近到远排列
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Trash {
+    pub teachers: Vec<DeletedTeacher>,
+    pub courses: Vec<DeletedCourse>,
+}
+
+/// 一次清理中物理删除的记录数
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeSummary {
+    pub teachers: u64,
+    pub courses: u64,
+}
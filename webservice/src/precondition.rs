@@ -0,0 +1,54 @@
+use actix_web::http::header::{self, EntityTag, Header, IfMatch, IfNoneMatch};
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+use crate::errors::MyError;
+
+/// 资源的 ETag：版本号加上响应体的哈希。
+/// 新增评价、课时等不会修改课程或教师的版本号，但会改变响应中的汇总字段，因此一并计入
+pub fn etag<T: Serialize>(version: i32, body: &T) -> EntityTag {
+    let hash = fnv1a(&serde_json::to_vec(body).unwrap_or_default());
+    EntityTag::new_strong(format!("{}-{:016x}", version, hash))
+}
+
+/// 64 位 FNV-1a。ETag 会被客户端保存，不能用 DefaultHasher：它的算法可能随 Rust 版本改变
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// 请求是否带有 If-Match，只有带了才需要读取当前资源来比较
+pub fn has_if_match(req: &HttpRequest) -> bool {
+    req.headers().contains_key(header::IF_MATCH)
+}
+
+/// If-Match 中没有与当前 ETag 强匹配的值时返回 PreconditionFailed，`*` 匹配任何存在的资源
+pub fn check_if_match(req: &HttpRequest, current: &EntityTag) -> Result<(), MyError> {
+    let matched = match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => true,
+        Ok(IfMatch::Items(tags)) => tags.iter().any(|tag| tag.strong_eq(current)),
+        Err(_) => return Err(MyError::InvalidInput("Invalid If-Match header".into())),
+    };
+    if matched {
+        Ok(())
+    } else {
+        Err(MyError::PreconditionFailed(
+            "Resource has been modified; fetch it again and retry".into(),
+        ))
+    }
+}
+
+/// GET 的响应：带上 ETag，If-None-Match 与之弱匹配时返回没有响应体的 304
+pub fn conditional_get<T: Serialize>(req: &HttpRequest, version: i32, body: &T) -> HttpResponse {
+    let tag = etag(version, body);
+    let not_modified = match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(&tag)),
+        Err(_) => false,
+    };
+    if not_modified {
+        HttpResponse::NotModified().insert_header(header::ETag(tag)).finish()
+    } else {
+        HttpResponse::Ok().insert_header(header::ETag(tag)).json(body)
+    }
+}
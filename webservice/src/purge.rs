@@ -0,0 +1,45 @@
+use actix_web::web;
+use chrono::Duration;
+use std::env;
+use std::time::Duration as IntervalDuration;
+use crate::dbaccess::{db_now, Repository};
+use crate::errors::MyError;
+use crate::models::audit::AuditContext;
+use crate::models::trash::PurgeSummary;
+use crate::state::AppState;
+
+/// 回收站默认保留 30 天，可以通过 TRASH_RETENTION_DAYS 修改
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// 后台清理任务的执行间隔
+const PURGE_INTERVAL: IntervalDuration = IntervalDuration::from_secs(60 * 60);
+
+/// 读取 TRASH_RETENTION_DAYS，未设置时使用默认值
+pub fn retention_from_env() -> Duration {
+    let days = env::var("TRASH_RETENTION_DAYS")
+        .map(|days| days.parse::<i64>().expect("TRASH_RETENTION_DAYS 必须是整数"))
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    Duration::days(days)
+}
+
+/// 物理删除在回收站中超过 retention 的教师和课程
+pub async fn purge_expired(db: &dyn Repository, retention: Duration) -> Result<PurgeSummary, MyError> {
+    db.purge_deleted_db(&AuditContext::default(), db_now() - retention).await
+}
+
+/// 启动后台清理任务，每小时执行一次
+pub fn spawn_purge_job(app_state: web::Data<AppState>, retention: Duration) {
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match purge_expired(app_state.db.as_ref(), retention).await {
+                Ok(summary) if summary != PurgeSummary::default() => {
+                    println!("Purged {} teacher(s) and {} course(s) from trash", summary.teachers, summary.courses)
+                }
+                Ok(_) => {}
+                Err(err) => println!("Failed to purge trash: {:?}", err),
+            }
+        }
+    });
+}
@@ -1,28 +1,109 @@
-use crate::handlers::{course::*, general::*, teacher::*};
+use crate::auth::require_role;
+use crate::handlers::{admin::*, audit::*, auth::*, batch::*, catalog::*, content::*, course::*, enrollment::*, general::*, review::*, student::*, teacher::*, trash::*};
+use crate::models::auth::Role;
+use crate::models::catalog::MAX_IMPORT_BYTES;
 use actix_web::web;
 
+/// 只有管理员可以访问
+const ADMIN: &[Role] = &[Role::Admin];
+/// 管理员和教师可以访问，教师还要通过处理器中的所有权检查
+const ADMIN_OR_TEACHER: &[Role] = &[Role::Admin, Role::Teacher];
+/// 只有学生可以访问
+const STUDENT: &[Role] = &[Role::Student];
+
 pub fn general_routes(cfg: &mut web::ServiceConfig) {
     cfg.route("/health", web::get().to(health_check_handler));
+    cfg.route("/meta/course-options", web::get().to(get_course_options));
+}
+
+/// 回收站：查看已删除的教师和课程，恢复接口分别在课程和教师路由中
+pub fn trash_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/trash", web::get().to(get_trash).wrap(require_role(ADMIN_OR_TEACHER)));
+}
+
+/// 审计日志：只有管理员可以查看
+pub fn audit_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/audit", web::get().to(get_audit_log).wrap(require_role(ADMIN)));
 }
 
 pub fn course_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/courses")
-            .route("/", web::post().to(post_new_course))
+            .route("", web::get().to(search_courses))
+            .route("/", web::post().to(post_new_course).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/batch", web::post().to(batch_courses).wrap(require_role(ADMIN_OR_TEACHER)))
             .route("/{teacher_id}", web::get().to(get_courses_for_teacher))
+            // 固定路径要在 /{course_id} 之前注册
+            .route("/{teacher_id}/export", web::get().to(export_courses))
+            .service(
+                web::resource("/{teacher_id}/import")
+                    .app_data(web::PayloadConfig::new(MAX_IMPORT_BYTES))
+                    .route(web::post().to(import_courses).wrap(require_role(ADMIN_OR_TEACHER)))
+            )
             .route("/{teacher_id}/{course_id}", web::get().to(get_course_detail))
-            .route("/{teacher_id}/{course_id}", web::delete().to(delete_course))
-            .route("/{teacher_id}/{course_id}", web::put().to(update_course_detail))
+            .route("/{teacher_id}/{course_id}", web::delete().to(delete_course).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}", web::put().to(update_course_detail).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}", web::patch().to(patch_course_detail).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/restore", web::post().to(restore_course).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/enrollments", web::get().to(get_course_roster).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/enrollments", web::post().to(enroll_in_course).wrap(require_role(STUDENT)))
+            .route("/{teacher_id}/{course_id}/enrollments/{student_id}", web::delete().to(withdraw_from_course))
+            .route("/{teacher_id}/{course_id}/modules", web::get().to(get_course_modules))
+            .route("/{teacher_id}/{course_id}/modules", web::post().to(post_new_module).wrap(require_role(ADMIN_OR_TEACHER)))
+            // 固定路径要在 /{module_id} 之前注册
+            .route("/{teacher_id}/{course_id}/modules/order", web::put().to(reorder_modules).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}", web::get().to(get_module_detail))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}", web::put().to(update_module).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}", web::delete().to(delete_module).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}/lessons", web::post().to(post_new_lesson).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}/lessons/order", web::put().to(reorder_lessons).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}/lessons/{lesson_id}", web::put().to(update_lesson).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/modules/{module_id}/lessons/{lesson_id}", web::delete().to(delete_lesson).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}/{course_id}/reviews", web::get().to(get_reviews_for_course))
+            .route("/{teacher_id}/{course_id}/reviews", web::post().to(post_new_review).wrap(require_role(STUDENT)))
+            .route("/{teacher_id}/{course_id}/reviews/{review_id}", web::get().to(get_review_detail))
+            .route("/{teacher_id}/{course_id}/reviews/{review_id}", web::put().to(update_review).wrap(require_role(STUDENT)))
+            .route("/{teacher_id}/{course_id}/reviews/{review_id}", web::delete().to(delete_review))
     );
 }
 
 pub fn teacher_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/teachers")
-            .route("/", web::post().to(post_new_teacher))
+            .route("/", web::post().to(post_new_teacher).wrap(require_role(ADMIN)))
             .route("/", web::get().to(get_all_teachers))
             .route("/{teacher_id}", web::get().to(get_teacher_detail))
-            .route("/{teacher_id}", web::put().to(update_teacher_detail))
-            .route("/{teacher_id}", web::delete().to(delete_teacher))
+            .route("/{teacher_id}", web::put().to(update_teacher_detail).wrap(require_role(ADMIN_OR_TEACHER)))
+            .route("/{teacher_id}", web::delete().to(delete_teacher).wrap(require_role(ADMIN)))
+            .route("/{teacher_id}/restore", web::post().to(restore_teacher).wrap(require_role(ADMIN)))
+    );
+}
+
+pub fn student_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/students")
+            .route("/{student_id}", web::get().to(get_student_detail))
+            .route("/{student_id}/courses", web::get().to(get_student_courses))
     );
-}
\ No newline at end of file
+}
+
+pub fn auth_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .route("/register", web::post().to(register_teacher))
+            .route("/register/student", web::post().to(register_student))
+            .route("/login", web::post().to(login))
+            .route("/refresh", web::post().to(refresh))
+            .route("/me", web::get().to(me))
+    );
+}
+
+pub fn admin_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .wrap(require_role(ADMIN))
+            .route("/accounts/{account_id}/roles", web::get().to(get_account_roles))
+            .route("/accounts/{account_id}/roles/{role}", web::put().to(grant_role))
+            .route("/accounts/{account_id}/roles/{role}", web::delete().to(revoke_role))
+    );
+}
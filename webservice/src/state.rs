@@ -1,10 +1,12 @@
 use std::sync::Mutex;
 // use super::models::Course;
-use sqlx::MySqlPool;
+use crate::auth::AuthConfig;
+use crate::dbaccess::Repository;
 
 pub struct AppState {
     pub health_check_response: String,
     pub visit_count: Mutex<u32>,
     // pub courses: Mutex<Vec<Course>>,
-    pub db: MySqlPool,
-}
\ No newline at end of file
+    pub db: Box<dyn Repository>,
+    pub auth: AuthConfig,
+}
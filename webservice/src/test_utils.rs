@@ -0,0 +1,177 @@
+use crate::auth::{AuthConfig, AuthenticatedUser};
+use crate::cors;
+use crate::errors::{json_error_handler, request_id, MyError};
+use crate::migration::SchemaMigrator;
+use crate::models::auth::{Account, Role};
+use crate::routers::{
+    admin_routes, audit_routes, auth_routes, course_routes, general_routes, student_routes,
+    teacher_routes, trash_routes,
+};
+use crate::state::AppState;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::from_fn;
+use actix_web::{test, web, App, Error};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Executor, SqlitePool};
+use std::sync::Mutex;
+
+/// 种子账号的登录密码，对应 SEED_SQL 中的 argon2 哈希
+pub const SEED_PASSWORD: &str = "password123";
+
+/// 种子账号：(用户名, 教师 id, 学生 id, 角色)
+type SeedAccount = (&'static str, Option<i32>, Option<i32>, &'static [Role]);
+
+/// 账号 1-3 依次属于教师 1-3，账号 4 是管理员，账号 5、6 依次属于学生 1、2
+const SEED_ACCOUNTS: [SeedAccount; 6] = [
+    ("teacher_one", Some(1), None, &[Role::Teacher]),
+    ("teacher_two", Some(2), None, &[Role::Teacher]),
+    ("teacher_three", Some(3), None, &[Role::Teacher]),
+    ("admin", None, None, &[Role::Admin]),
+    ("student_one", None, Some(1), &[Role::Student]),
+    ("student_two", None, Some(2), &[Role::Student]),
+];
+
+/// 测试用的种子数据：教师 1 有课程 1、2，教师 2 有课程 3（容量为 1），教师 3 没有课程；
+/// 课程 1 有两个章节共 90 分钟的课时；学生 1 已选修课程 1 并给出 4 分的评价。账号见 SEED_ACCOUNTS
+const SEED_SQL: &str = r#"
+INSERT INTO teacher (id, name, picture_url, profile) VALUES
+    (1, 'Teacher One', 'https://example.com/one.jpg', 'Profile of teacher one'),
+    (2, 'Teacher Two', 'https://example.com/two.jpg', 'Profile of teacher two'),
+    (3, 'Teacher Three', 'https://example.com/three.jpg', 'Profile of teacher three');
+
+INSERT INTO course (id, teacher_id, name, time, description, format, price, language, level, capacity) VALUES
+    (1, 1, 'First course', '2025-07-12 10:15:00', 'Course one of teacher one', 'Video', 100, 'English', 'Beginner', NULL),
+    (2, 1, 'Second course', '2025-07-13 10:15:00', 'Course two of teacher one', 'Live', 200, 'English', 'Intermediate', NULL),
+    (3, 2, 'Third course', '2025-07-14 10:15:00', 'Course one of teacher two', 'Video', 150, 'Chinese', 'Beginner', 1);
+
+INSERT INTO account (id, username, password_hash, teacher_id) VALUES
+    (1, 'teacher_one', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', 1),
+    (2, 'teacher_two', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', 2),
+    (3, 'teacher_three', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', 3),
+    (4, 'admin', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', NULL),
+    (5, 'student_one', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', NULL),
+    (6, 'student_two', '$argon2id$v=19$m=19456,t=2,p=1$HaNAMBLryjkzvcyPIkn7jg$kBhJ7oyOiHMf+iNjghBmkoSZEyKg+dFOOmy+CAXpLtQ', NULL);
+
+INSERT INTO account_role (account_id, role) VALUES
+    (1, 'teacher'),
+    (2, 'teacher'),
+    (3, 'teacher'),
+    (4, 'admin'),
+    (5, 'student'),
+    (6, 'student');
+
+INSERT INTO student (id, account_id, name, email) VALUES
+    (1, 5, 'Student One', 'one@example.com'),
+    (2, 6, 'Student Two', 'two@example.com');
+
+INSERT INTO enrollment (id, course_id, student_id, status, enrolled_at) VALUES
+    (1, 1, 1, 'enrolled', '2025-08-01 09:00:00');
+
+INSERT INTO course_module (id, course_id, title, position) VALUES
+    (1, 1, 'Getting started', 1),
+    (2, 1, 'Going further', 2);
+
+INSERT INTO lesson (id, module_id, title, body, estimated_minutes, attachment_url, position) VALUES
+    (1, 1, 'Installation', 'Install the toolchain', 30, NULL, 1),
+    (2, 1, 'Hello world', NULL, 45, 'https://example.com/hello.zip', 2),
+    (3, 2, 'Next steps', NULL, 15, NULL, 1);
+
+INSERT INTO review (id, course_id, student_id, rating, comment, created_at, updated_at) VALUES
+    (1, 1, 1, 4, 'Clear and well paced', '2025-08-10 12:00:00', '2025-08-10 12:00:00');
+"#;
+
+/// 为单个测试创建独立的内存 SQLite 数据库，执行迁移并写入种子数据
+pub async fn create_test_pool() -> SqlitePool {
+    // 内存数据库只存在于创建它的连接中，连接池里只保留这一个连接
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    pool.run_pending_migrations().await.unwrap();
+    pool.execute(SEED_SQL).await.unwrap();
+
+    pool
+}
+
+pub async fn create_app_state() -> web::Data<AppState> {
+    web::Data::new(AppState {
+        health_check_response: "".to_string(),
+        visit_count: Mutex::new(0),
+        db: Box::new(create_test_pool().await),
+        auth: AuthConfig::new("test-secret"),
+    })
+}
+
+fn seed_account(account_id: i32) -> (Account, &'static [Role]) {
+    let (username, teacher_id, student_id, roles) = SEED_ACCOUNTS[account_id as usize - 1];
+    let account = Account {
+        id: account_id,
+        username: username.to_string(),
+        password_hash: String::new(),
+        teacher_id,
+        student_id,
+    };
+    (account, roles)
+}
+
+/// 种子账号对应的调用者身份，用于直接调用处理器函数
+pub fn seed_user(account_id: i32) -> AuthenticatedUser {
+    let (account, roles) = seed_account(account_id);
+    AuthenticatedUser {
+        account_id: account.id,
+        username: account.username,
+        teacher_id: account.teacher_id,
+        student_id: account.student_id,
+        roles: roles.to_vec(),
+    }
+}
+
+/// 为种子账号签发 access token，返回可直接传给 `insert_header` 的 Authorization 头
+pub fn bearer(app_state: &web::Data<AppState>, account_id: i32) -> (header::HeaderName, String) {
+    let (account, roles) = seed_account(account_id);
+    let tokens = app_state.auth.issue_tokens(&account, roles).unwrap();
+    (header::AUTHORIZATION, format!("Bearer {}", tokens.access_token))
+}
+
+/// 与 teacher_service 相同的路由、JSON 配置和中间件，用于 `test::init_service`
+pub fn create_app(
+    app_state: web::Data<AppState>,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse,
+        Error = Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        .app_data(app_state)
+        .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+        .app_data(web::QueryConfig::default().error_handler(|_err, _req| {
+            MyError::InvalidInput("Please provide valid query parameters".to_string()).into()
+        }))
+        .configure(general_routes)
+        .configure(auth_routes)
+        .configure(admin_routes)
+        .configure(course_routes)
+        .configure(teacher_routes)
+        .configure(student_routes)
+        .configure(trash_routes)
+        .configure(audit_routes)
+        .wrap(cors())
+        .wrap(from_fn(request_id))
+}
+
+/// 读取错误响应体并去掉每次都不同的 request_id，便于整体比较
+pub async fn read_error(resp: ServiceResponse) -> serde_json::Value {
+    let mut body: serde_json::Value = test::read_body_json(resp).await;
+    let request_id = body.as_object_mut().and_then(|body| body.remove("request_id"));
+    assert!(request_id.is_some(), "error response should carry a request_id");
+    body
+}
@@ -0,0 +1,108 @@
+use crate::errors::{FieldError, MyError};
+use std::str::FromStr;
+use url::Url;
+
+/// 收集请求中全部字段的校验错误，最后通过 finish 一次性返回
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    pub fn add(mut self, field: &str, message: impl Into<String>) -> Self {
+        self.errors.push(FieldError {
+            field: field.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// 必填文本：去掉首尾空白后不能为空，且不超过 max 个字符
+    pub fn required(self, field: &str, value: &str, max: usize) -> Self {
+        if value.trim().is_empty() {
+            self.add(field, "must not be empty")
+        } else {
+            self.max_length(field, Some(value), max)
+        }
+    }
+
+    /// 提供时按必填文本校验，用于部分修改时可以缺省但不能清空的字段
+    pub fn optional(self, field: &str, value: Option<&str>, max: usize) -> Self {
+        match value {
+            Some(value) => self.required(field, value, max),
+            None => self,
+        }
+    }
+
+    /// 可选文本：提供时不超过 max 个字符
+    pub fn max_length(self, field: &str, value: Option<&str>, max: usize) -> Self {
+        match value {
+            Some(value) if value.chars().count() > max => {
+                self.add(field, format!("must be at most {} characters", max))
+            }
+            _ => self,
+        }
+    }
+
+    /// 至少 min 个字符，用于密码等不去除空白的文本
+    pub fn min_length(self, field: &str, value: &str, min: usize) -> Self {
+        if value.chars().count() < min {
+            self.add(field, format!("must be at least {} characters", min))
+        } else {
+            self
+        }
+    }
+
+    /// 提供时必须是 http 或 https 的绝对地址
+    pub fn url(self, field: &str, value: Option<&str>) -> Self {
+        let valid = |value: &str| {
+            Url::parse(value)
+                .map(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+                .unwrap_or(false)
+        };
+        match value {
+            Some(value) if !valid(value) => self.add(field, "must be a valid http or https URL"),
+            _ => self,
+        }
+    }
+
+    /// 提供时不能小于 min
+    pub fn at_least(self, field: &str, value: Option<i32>, min: i32) -> Self {
+        match value {
+            Some(value) if value < min => self.add(field, format!("must be at least {}", min)),
+            _ => self,
+        }
+    }
+
+    /// 提供时必须在 min 到 max 之间（含两端）
+    pub fn between(self, field: &str, value: Option<i32>, min: i32, max: i32) -> Self {
+        match value {
+            Some(value) if !(min..=max).contains(&value) => {
+                self.add(field, format!("must be between {} and {}", min, max))
+            }
+            _ => self,
+        }
+    }
+
+    /// 提供时必须是 T 的合法取值，错误信息沿用 T 的解析错误
+    pub fn one_of<T: FromStr<Err = MyError>>(self, field: &str, value: Option<&str>) -> Self {
+        match value.map(str::parse::<T>) {
+            Some(Err(MyError::InvalidInput(message))) => self.add(field, message),
+            Some(Err(_)) => self.add(field, "is not a valid value"),
+            _ => self,
+        }
+    }
+
+    /// 没有错误时返回 Ok，否则把全部错误放在一个 Validation 错误中返回
+    pub fn finish(self) -> Result<(), MyError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MyError::Validation(self.errors))
+        }
+    }
+}